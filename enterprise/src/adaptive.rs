@@ -0,0 +1,248 @@
+use crate::policy::ExplorerDemand;
+
+/// Built-in energy policies the planet can switch between
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnergyPolicy {
+    Balanced,       // The configured behaviour, unchanged
+    DefenseFirst,   // The cell is kept for the explorers only once a rocket is ready
+    ExplorersFirst, // The cell is kept for any explorer on the planet, even idle or low priority ones
+}
+
+impl EnergyPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EnergyPolicy::Balanced => "balanced",
+            EnergyPolicy::DefenseFirst => "defense_first",
+            EnergyPolicy::ExplorersFirst => "explorers_first",
+        }
+    }
+
+    // Adjusts the outcome of the demand check to the policy
+    pub(crate) fn apply(self, demand: ExplorerDemand, has_rocket: bool) -> ExplorerDemand {
+        match self {
+            EnergyPolicy::Balanced => demand,
+            EnergyPolicy::DefenseFirst if demand.saves_energy() && !has_rocket => {
+                ExplorerDemand::DefenseFirst
+            }
+            EnergyPolicy::ExplorersFirst
+                if matches!(
+                    demand,
+                    ExplorerDemand::IdleExplorers | ExplorerDemand::LowPriorityExplorers
+                ) =>
+            {
+                ExplorerDemand::ExplorersPresent
+            }
+            _ => demand,
+        }
+    }
+}
+
+/// Weights of the events that make up the reward of a policy
+#[derive(Debug, Clone)]
+pub struct RewardWeights {
+    pub asteroid_survived: f64,
+    pub asteroid_hit: f64,     // Penalty for an asteroid the planet had no rocket for
+    pub cell_served: f64,      // Charged cell consumed by an explorer
+    pub request_unserved: f64, // Penalty for a request made while no cell was charged
+    pub sunray_wasted: f64,    // Penalty for a sunray that found the cell already charged
+}
+
+impl Default for RewardWeights {
+    fn default() -> Self {
+        Self {
+            asteroid_survived: 5.0,
+            asteroid_hit: 50.0,
+            cell_served: 1.0,
+            request_unserved: 0.5,
+            sunray_wasted: 0.25,
+        }
+    }
+}
+
+/// Self-tuning mode: an epsilon-greedy bandit picks the energy policy for each epoch
+#[derive(Debug, Clone)]
+pub struct AdaptiveConfig {
+    pub policies: Vec<EnergyPolicy>, // Policies to choose from, none disables the self-tuning
+    pub epoch_sunrays: u32,          // Sunrays a policy is kept for before its reward is scored
+    pub exploration: f64,            // Probability of trying a random policy instead of the best one
+    pub seed: u64,                   // Runs with the same seed and events make the same choices
+    pub rewards: RewardWeights,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        Self {
+            policies: Vec::new(),
+            epoch_sunrays: 10,
+            exploration: 0.1,
+            seed: 0,
+            rewards: RewardWeights::default(),
+        }
+    }
+}
+
+/// Rewards collected by a policy, as seen by the host
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyStats {
+    pub policy: EnergyPolicy,
+    pub epochs: u64,
+    pub mean_reward: f64, // Per sunray
+}
+
+// SplitMix64, small and good enough to pick policies, with no dependency
+#[derive(Debug, Clone)]
+pub(crate) struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1)
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+// Events of the current epoch
+#[derive(Debug, Default, Clone, Copy)]
+struct Epoch {
+    sunrays: u32,
+    asteroids_survived: u32,
+    asteroids_hit: u32,
+    cells_served: u32,
+    requests_unserved: u32,
+    sunrays_wasted: u32,
+}
+
+#[derive(Debug)]
+pub(crate) struct PolicyLearner {
+    config: AdaptiveConfig,
+    rng: SeededRng,
+    current: usize, // Index of the policy played in this epoch
+    epoch: Epoch,
+    stats: Vec<PolicyStats>,
+}
+
+impl PolicyLearner {
+    pub(crate) fn new(config: AdaptiveConfig) -> Self {
+        let mut rng = SeededRng::new(config.seed);
+        let stats = config
+            .policies
+            .iter()
+            .map(|policy| PolicyStats {
+                policy: *policy,
+                epochs: 0,
+                mean_reward: 0.0,
+            })
+            .collect::<Vec<_>>();
+        let current = if stats.is_empty() { 0 } else { rng.below(stats.len()) };
+        Self {
+            config,
+            rng,
+            current,
+            epoch: Epoch::default(),
+            stats,
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.stats.is_empty()
+    }
+
+    pub(crate) fn policy(&self) -> EnergyPolicy {
+        self.stats
+            .get(self.current)
+            .map_or(EnergyPolicy::Balanced, |stats| stats.policy)
+    }
+
+    fn reward(&self) -> f64 {
+        let weights = &self.config.rewards;
+        let epoch = &self.epoch;
+        let reward = weights.asteroid_survived * epoch.asteroids_survived as f64
+            - weights.asteroid_hit * epoch.asteroids_hit as f64
+            + weights.cell_served * epoch.cells_served as f64
+            - weights.request_unserved * epoch.requests_unserved as f64
+            - weights.sunray_wasted * epoch.sunrays_wasted as f64;
+        reward / epoch.sunrays.max(1) as f64
+    }
+
+    // Scores the policy of the epoch and picks the next one.
+    // Policies never played come first, then the best one, except for the exploration.
+    fn close_epoch(&mut self) {
+        let reward = self.reward();
+        let stats = &mut self.stats[self.current];
+        stats.epochs += 1;
+        stats.mean_reward += (reward - stats.mean_reward) / stats.epochs as f64;
+        self.epoch = Epoch::default();
+
+        let unplayed = self
+            .stats
+            .iter()
+            .enumerate()
+            .filter(|(_, stats)| stats.epochs == 0)
+            .map(|(at, _)| at)
+            .collect::<Vec<_>>();
+        self.current = if !unplayed.is_empty() {
+            unplayed[self.rng.below(unplayed.len())]
+        } else if self.rng.next_f64() < self.config.exploration {
+            self.rng.below(self.stats.len())
+        } else {
+            self.stats
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.mean_reward.total_cmp(&b.mean_reward))
+                .map_or(0, |(at, _)| at)
+        };
+    }
+
+    // Counts a sunray, returns true if it ended the epoch and the policy may have changed
+    pub(crate) fn record_sunray(&mut self, wasted: bool) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        self.epoch.sunrays += 1;
+        if wasted {
+            self.epoch.sunrays_wasted += 1;
+        }
+        if self.epoch.sunrays >= self.config.epoch_sunrays.max(1) {
+            self.close_epoch();
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn record_asteroid(&mut self, survived: bool) {
+        if survived {
+            self.epoch.asteroids_survived += 1;
+        } else {
+            self.epoch.asteroids_hit += 1;
+        }
+    }
+
+    pub(crate) fn record_cell_served(&mut self) {
+        self.epoch.cells_served += 1;
+    }
+
+    pub(crate) fn record_unserved(&mut self) {
+        self.epoch.requests_unserved += 1;
+    }
+
+    pub(crate) fn stats(&self) -> Vec<PolicyStats> {
+        self.stats.clone()
+    }
+}
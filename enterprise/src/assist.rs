@@ -0,0 +1,113 @@
+use common_game::components::resource::{
+    BasicResource, ComplexResource, ComplexResourceRequest, ComplexResourceType, GenericResource,
+};
+use common_game::utils::ID;
+use std::collections::{HashMap, VecDeque};
+
+/// Assisted crafting rules: which recipes the planet helps with and how much Carbon it gives
+#[derive(Debug, Clone, Default)]
+pub struct AssistConfig {
+    pub recipes: HashMap<ComplexResourceType, u32>, // Stockpiled Carbon the planet supplies per craft of each recipe
+    pub max_carbon_per_explorer: Option<u32>,        // Carbon an explorer can receive over the whole game
+}
+
+/// Assisted carbon received by an explorer, as seen by the host
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssistUsage {
+    pub crafts: u64,          // Assisted crafts completed
+    pub failures: u64,        // Assisted crafts rejected, the inputs were given back
+    pub carbon_supplied: u32, // Stockpiled Carbon used in the completed crafts
+}
+
+/// Outcome of an assisted craft, collected by the host on behalf of the explorer
+#[derive(Debug)]
+pub struct AssistedCraft {
+    pub resource: ComplexResourceType,
+    pub carbon_supplied: u32,
+    // The crafted resource, or the reason of the failure with the explorer inputs given back
+    pub result: Result<ComplexResource, (String, Vec<GenericResource>)>,
+}
+
+#[derive(Debug)]
+pub(crate) struct AssistOrder {
+    pub(crate) explorer_id: ID,
+    pub(crate) resource: ComplexResourceType,
+    pub(crate) provided: Vec<GenericResource>, // Inputs brought by the explorer
+}
+
+// Orders placed by the host, waiting for the planet thread, and their outcomes
+#[derive(Debug, Default)]
+pub(crate) struct AssistBook {
+    config: AssistConfig,
+    pub(crate) pending: VecDeque<AssistOrder>,
+    outbox: HashMap<ID, Vec<AssistedCraft>>,
+    usage: HashMap<ID, AssistUsage>,
+}
+
+impl AssistBook {
+    pub(crate) fn new(config: AssistConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    // Queues an order for the planet thread, giving the inputs back if the recipe isn't assisted
+    pub(crate) fn order(&mut self, order: AssistOrder) -> Result<(), Vec<GenericResource>> {
+        if !self.config.recipes.contains_key(&order.resource) {
+            return Err(order.provided);
+        }
+        self.pending.push_back(order);
+        Ok(())
+    }
+
+    pub(crate) fn complete(&mut self, explorer_id: ID, craft: AssistedCraft) {
+        let usage = self.usage.entry(explorer_id).or_default();
+        match craft.result {
+            Ok(_) => {
+                usage.crafts += 1;
+                usage.carbon_supplied += craft.carbon_supplied;
+            }
+            Err(_) => usage.failures += 1,
+        }
+        self.outbox.entry(explorer_id).or_default().push(craft);
+    }
+
+    pub(crate) fn take(&mut self, explorer_id: ID) -> Vec<AssistedCraft> {
+        self.outbox.remove(&explorer_id).unwrap_or_default()
+    }
+
+    pub(crate) fn usage(&self, explorer_id: ID) -> Option<AssistUsage> {
+        self.usage.get(&explorer_id).cloned()
+    }
+}
+
+// Builds the combination request out of two inputs matching the recipe, gives them back otherwise
+pub(crate) fn combination_request(
+    resource: ComplexResourceType,
+    r1: GenericResource,
+    r2: GenericResource,
+) -> Result<ComplexResourceRequest, (GenericResource, GenericResource)> {
+    use GenericResource::{BasicResources as B, ComplexResources as C};
+    match (resource, r1, r2) {
+        (ComplexResourceType::Water, B(BasicResource::Hydrogen(h)), B(BasicResource::Oxygen(o))) => {
+            Ok(ComplexResourceRequest::Water(h, o))
+        }
+        (ComplexResourceType::Diamond, B(BasicResource::Carbon(c1)), B(BasicResource::Carbon(c2))) => {
+            Ok(ComplexResourceRequest::Diamond(c1, c2))
+        }
+        (ComplexResourceType::Life, C(ComplexResource::Water(w)), B(BasicResource::Carbon(c))) => {
+            Ok(ComplexResourceRequest::Life(w, c))
+        }
+        (ComplexResourceType::Robot, B(BasicResource::Silicon(s)), C(ComplexResource::Life(l))) => {
+            Ok(ComplexResourceRequest::Robot(s, l))
+        }
+        (ComplexResourceType::Dolphin, C(ComplexResource::Water(w)), C(ComplexResource::Life(l))) => {
+            Ok(ComplexResourceRequest::Dolphin(w, l))
+        }
+        (ComplexResourceType::AIPartner, C(ComplexResource::Robot(r)), C(ComplexResource::Diamond(d))) => {
+            Ok(ComplexResourceRequest::AIPartner(r, d))
+        }
+        (_, r1, r2) => Err((r1, r2)),
+    }
+}
@@ -0,0 +1,182 @@
+use crate::refusal::RefusalReason;
+use common_game::components::resource::GenericResource;
+use common_game::utils::ID;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Auction mode: explorers bid for the next charged cell instead of racing for it
+#[derive(Debug, Clone)]
+pub struct AuctionConfig {
+    pub bidding_window: Option<Duration>, // Time from the first bid to the award, `None` disables auctions
+    pub reservation_timeout: Duration,    // The winner loses the cell if it doesn't use it in time
+}
+
+impl Default for AuctionConfig {
+    fn default() -> Self {
+        Self {
+            bidding_window: None,
+            reservation_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Outcome of an auction, as seen by the host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuctionResult {
+    pub winner: ID,
+    pub priority: u32,
+    pub offered: usize, // Resources the winner paid with
+    pub bids: usize,
+    pub settled_at: Instant,
+}
+
+#[derive(Debug)]
+struct Bid {
+    explorer_id: ID,
+    priority: u32,
+    offered: Vec<GenericResource>,
+    at: Instant,
+}
+
+// Bids placed by the host and the cell reserved for the last winner
+#[derive(Debug, Default)]
+pub(crate) struct AuctionBook {
+    config: AuctionConfig,
+    bids: Vec<Bid>,
+    opened_at: Option<Instant>,
+    reserved_for: Option<(ID, Instant)>, // Winner and when the reservation expires
+    refunds: HashMap<ID, Vec<GenericResource>>,
+    results: Vec<AuctionResult>,
+}
+
+impl AuctionBook {
+    pub(crate) fn new(config: AuctionConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    // Registers a bid, replacing the previous one of the explorer. Gives the resources back if
+    // auctions are disabled.
+    pub(crate) fn bid(
+        &mut self,
+        explorer_id: ID,
+        priority: u32,
+        offered: Vec<GenericResource>,
+        now: Instant,
+    ) -> Result<(), Vec<GenericResource>> {
+        if self.config.bidding_window.is_none() {
+            return Err(offered);
+        }
+        if let Some(at) = self.bids.iter().position(|bid| bid.explorer_id == explorer_id) {
+            let previous = self.bids.remove(at);
+            self.refund(explorer_id, previous.offered);
+        }
+        self.opened_at.get_or_insert(now);
+        self.bids.push(Bid {
+            explorer_id,
+            priority,
+            offered,
+            at: now,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn refund(&mut self, explorer_id: ID, resources: Vec<GenericResource>) {
+        if !resources.is_empty() {
+            self.refunds.entry(explorer_id).or_default().extend(resources);
+        }
+    }
+
+    // Closes the auction once the bidding window is over. Returns the result and the winner payment.
+    pub(crate) fn settle(&mut self, now: Instant) -> Option<(AuctionResult, Vec<GenericResource>)> {
+        if let Some((_, until)) = self.reserved_for
+            && now >= until
+        {
+            self.reserved_for = None;
+        }
+
+        let window = self.config.bidding_window?;
+        let opened_at = self.opened_at?;
+        if now.saturating_duration_since(opened_at) < window || self.reserved_for.is_some() {
+            return None;
+        }
+
+        // Highest priority first, then the richest offer, then the earliest bid
+        let winner_at = self
+            .bids
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, bid)| (bid.priority, bid.offered.len(), Reverse(bid.at)))
+            .map(|(at, _)| at)?;
+        let winner = self.bids.remove(winner_at);
+        let bids = self.bids.len() + 1;
+        for loser in std::mem::take(&mut self.bids) {
+            self.refund(loser.explorer_id, loser.offered);
+        }
+        self.opened_at = None;
+        self.reserved_for = Some((winner.explorer_id, now + self.config.reservation_timeout));
+
+        let result = AuctionResult {
+            winner: winner.explorer_id,
+            priority: winner.priority,
+            offered: winner.offered.len(),
+            bids,
+            settled_at: now,
+        };
+        self.results.push(result.clone());
+        Some((result, winner.offered))
+    }
+
+    // Only the winner gets the next cell, and nobody else does while bids are open.
+    // New bids don't take the reserved cell away from the winner.
+    pub(crate) fn check_cell(&self, explorer_id: ID, now: Instant) -> Result<(), RefusalReason> {
+        match self.reserved_for {
+            Some((winner, until)) if now < until && winner != explorer_id => {
+                Err(RefusalReason::CellReserved)
+            }
+            Some((_, until)) if now < until => Ok(()),
+            _ if self.opened_at.is_some() => Err(RefusalReason::AuctionInProgress),
+            _ => Ok(()),
+        }
+    }
+
+    // The winner got its cell, returns true if it was holding a reservation
+    pub(crate) fn delivered(&mut self, explorer_id: ID) -> bool {
+        if self.reserved_for.is_some_and(|(winner, _)| winner == explorer_id) {
+            self.reserved_for = None;
+            return true;
+        }
+        false
+    }
+
+    // Drops the reservation and the bid of an explorer leaving the planet
+    pub(crate) fn withdraw(&mut self, explorer_id: ID) {
+        if self.reserved_for.is_some_and(|(winner, _)| winner == explorer_id) {
+            self.reserved_for = None;
+        }
+        if let Some(at) = self.bids.iter().position(|bid| bid.explorer_id == explorer_id) {
+            let bid = self.bids.remove(at);
+            self.refund(explorer_id, bid.offered);
+        }
+        if self.bids.is_empty() {
+            self.opened_at = None;
+        }
+    }
+
+    pub(crate) fn reserved_for(&self, now: Instant) -> Option<ID> {
+        self.reserved_for
+            .filter(|(_, until)| now < *until)
+            .map(|(winner, _)| winner)
+    }
+
+    pub(crate) fn take_refunds(&mut self, explorer_id: ID) -> Vec<GenericResource> {
+        self.refunds.remove(&explorer_id).unwrap_or_default()
+    }
+
+    pub(crate) fn results(&self) -> Vec<AuctionResult> {
+        self.results.clone()
+    }
+}
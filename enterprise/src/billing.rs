@@ -0,0 +1,120 @@
+use crate::refusal::RefusalReason;
+use common_game::components::resource::ResourceType;
+use common_game::utils::ID;
+use std::collections::HashMap;
+
+/// Credits charged to the explorers for the resources they get
+#[derive(Debug, Clone)]
+pub struct BillingConfig {
+    pub costs: HashMap<ResourceType, u64>, // Cost of each resource, the default cost for the ones not listed
+    pub default_cost: u64,                 // Every generation and combination costs one charged cell
+    pub budget: Option<u64>,               // Credits each explorer can spend, `None` only keeps the bills
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        Self {
+            costs: HashMap::new(),
+            default_cost: 1,
+            budget: None,
+        }
+    }
+}
+
+/// Charges for a resource on an explorer bill
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BillItem {
+    pub quantity: u64,
+    pub credits: u64,
+}
+
+/// Account of an explorer, as seen by the host
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Account {
+    pub spent: u64,
+    pub budget: Option<u64>, // `None` if the explorer can spend without limits
+    pub items: HashMap<ResourceType, BillItem>,
+}
+
+impl Account {
+    // Credits left, `None` if the budget is unlimited
+    pub fn remaining(&self) -> Option<u64> {
+        self.budget.map(|budget| budget.saturating_sub(self.spent))
+    }
+}
+
+// Accounts of the explorers and the budgets set by the host
+#[derive(Debug, Default)]
+pub(crate) struct BillingBook {
+    config: BillingConfig,
+    budgets: HashMap<ID, Option<u64>>, // Overrides of the configured budget
+    accounts: HashMap<ID, Account>,
+}
+
+impl BillingBook {
+    pub(crate) fn new(config: BillingConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn cost(&self, resource: ResourceType) -> u64 {
+        self.config
+            .costs
+            .get(&resource)
+            .copied()
+            .unwrap_or(self.config.default_cost)
+    }
+
+    fn budget_of(&self, explorer_id: ID) -> Option<u64> {
+        self.budgets
+            .get(&explorer_id)
+            .copied()
+            .unwrap_or(self.config.budget)
+    }
+
+    pub(crate) fn set_budget(&mut self, explorer_id: ID, budget: Option<u64>) {
+        self.budgets.insert(explorer_id, budget);
+    }
+
+    fn spent(&self, explorer_id: ID) -> u64 {
+        self.accounts.get(&explorer_id).map_or(0, |account| account.spent)
+    }
+
+    // Explorers can't get a resource they can't afford
+    pub(crate) fn check(&self, explorer_id: ID, resource: ResourceType) -> Result<(), RefusalReason> {
+        match self.budget_of(explorer_id) {
+            Some(budget) if self.spent(explorer_id) + self.cost(resource) > budget => {
+                Err(RefusalReason::BudgetExceeded)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Charges a resource handed out to the explorer, returns the credits charged
+    pub(crate) fn charge(&mut self, explorer_id: ID, resource: ResourceType) -> u64 {
+        let cost = self.cost(resource);
+        let account = self.accounts.entry(explorer_id).or_default();
+        account.spent += cost;
+        let item = account.items.entry(resource).or_default();
+        item.quantity += 1;
+        item.credits += cost;
+        cost
+    }
+
+    pub(crate) fn account(&self, explorer_id: ID) -> Account {
+        Account {
+            budget: self.budget_of(explorer_id),
+            ..self.accounts.get(&explorer_id).cloned().unwrap_or_default()
+        }
+    }
+
+    // Bills of every explorer that was charged something
+    pub(crate) fn bills(&self) -> HashMap<ID, Account> {
+        self.accounts
+            .keys()
+            .map(|explorer_id| (*explorer_id, self.account(*explorer_id)))
+            .collect()
+    }
+}
@@ -0,0 +1,18 @@
+// Prints the Enterprise recipe graph in Graphviz DOT, or in JSON with `--json`
+//
+// cargo run --bin recipe_graph | dot -Tpng -o recipes.png
+// cargo run --bin recipe_graph -- --json
+
+use enterprise::RecipeGraph;
+
+fn main() {
+    let graph = RecipeGraph::enterprise();
+    match std::env::args().nth(1).as_deref() {
+        None | Some("--dot") => print!("{}", graph.to_dot()),
+        Some("--json") => print!("{}", graph.to_json()),
+        Some(other) => {
+            eprintln!("unknown option {other}, expected --dot or --json");
+            std::process::exit(1);
+        }
+    }
+}
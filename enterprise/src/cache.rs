@@ -0,0 +1,160 @@
+use common_game::components::resource::{ComplexResource, ComplexResourceType, GenericResource, ResourceType};
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+/// Complex resources the planet pre-crafts with idle energy
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub resources: Vec<ComplexResourceType>, // Resources worth pre-crafting, the first ones are preferred
+    pub capacity: usize,                     // Pre-crafted resources kept at the same time
+    pub max_inputs: usize,                   // Donated and paid inputs kept, the oldest are dropped first
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            resources: Vec::new(),
+            capacity: 2,
+            max_inputs: 8,
+        }
+    }
+}
+
+/// Cache activity, as seen by the host
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub crafted: u64,   // Resources pre-crafted
+    pub hits: u64,      // Combination requests served from the cache
+    pub evicted: u64,   // Pre-crafted resources dropped to make room for more popular ones
+    pub donations: u64, // Inputs absorbed from the explorers served by the cache
+    pub payments: u64,  // Inputs kept from the auction winners
+    pub cached: HashMap<ComplexResourceType, usize>,
+}
+
+// Pre-crafted resources and the inputs used to craft them
+#[derive(Debug, Default)]
+pub(crate) struct CraftCache {
+    config: CacheConfig,
+    items: Vec<(ComplexResource, Instant)>, // With the time they were crafted
+    inputs: VecDeque<GenericResource>, // Donated by explorers or paid by auction winners
+    requests: HashMap<ComplexResourceType, u64>, // Popularity of each resource
+    stats: CacheStats,
+}
+
+impl CraftCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.config.resources.is_empty() && self.config.capacity > 0
+    }
+
+    pub(crate) fn record_request(&mut self, resource: ComplexResourceType) {
+        *self.requests.entry(resource).or_default() += 1;
+    }
+
+    fn popularity(&self, resource: ComplexResourceType) -> u64 {
+        self.requests.get(&resource).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn take(&mut self, resource: ComplexResourceType) -> Option<ComplexResource> {
+        let at = self
+            .items
+            .iter()
+            .position(|(item, _)| item.get_type() == resource)?;
+        self.stats.hits += 1;
+        Some(self.items.remove(at).0)
+    }
+
+    pub(crate) fn donate(&mut self, resource: GenericResource) {
+        if self.config.max_inputs == 0 {
+            return;
+        }
+        if self.inputs.len() >= self.config.max_inputs {
+            self.inputs.pop_front();
+        }
+        self.inputs.push_back(resource);
+        self.stats.donations += 1;
+    }
+
+    pub(crate) fn take_input(&mut self, input: ResourceType) -> Option<GenericResource> {
+        let at = self.inputs.iter().position(|r| r.get_type() == input)?;
+        self.inputs.remove(at)
+    }
+
+    // Keeps an input paid to the planet, gives it back if the cache is disabled or has no room
+    pub(crate) fn accept(&mut self, resource: GenericResource) -> Result<(), GenericResource> {
+        if !self.is_enabled() || self.inputs.len() >= self.config.max_inputs {
+            return Err(resource);
+        }
+        self.inputs.push_back(resource);
+        self.stats.payments += 1;
+        Ok(())
+    }
+
+    // Puts back an input that couldn't be used. It goes with the oldest ones, so it is the one
+    // dropped if the cache has no room left.
+    pub(crate) fn return_input(&mut self, resource: GenericResource) {
+        if self.inputs.len() < self.config.max_inputs {
+            self.inputs.push_front(resource);
+        }
+    }
+
+    // Least popular cached resource, the first to be evicted
+    fn eviction_candidate(&self) -> Option<ComplexResourceType> {
+        self.items
+            .iter()
+            .map(|(item, _)| item.get_type())
+            .min_by_key(|resource| self.popularity(*resource))
+    }
+
+    // Resources worth crafting now, most requested first
+    pub(crate) fn targets(&self) -> Vec<ComplexResourceType> {
+        let mut targets = self.config.resources.clone();
+        if self.items.len() >= self.config.capacity {
+            // A full cache only makes room for something more popular than what it holds
+            let Some(least) = self.eviction_candidate() else {
+                return Vec::new();
+            };
+            let floor = self.popularity(least);
+            targets.retain(|resource| self.popularity(*resource) > floor);
+        }
+        targets.sort_by_key(|resource| Reverse(self.popularity(*resource)));
+        targets
+    }
+
+    // Stores a pre-crafted resource, returns the type of the resource evicted to make room
+    pub(crate) fn insert(&mut self, resource: ComplexResource, now: Instant) -> Option<ComplexResourceType> {
+        let mut evicted = None;
+        if self.items.len() >= self.config.capacity
+            && let Some(least) = self.eviction_candidate()
+            && let Some(at) = self
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(_, (item, _))| item.get_type() == least)
+                .min_by_key(|(_, (_, crafted_at))| *crafted_at)
+                .map(|(at, _)| at)
+        {
+            self.items.remove(at);
+            self.stats.evicted += 1;
+            evicted = Some(least);
+        }
+        self.items.push((resource, now));
+        self.stats.crafted += 1;
+        evicted
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        let mut stats = self.stats.clone();
+        for (item, _) in &self.items {
+            *stats.cached.entry(item.get_type()).or_default() += 1;
+        }
+        stats
+    }
+}
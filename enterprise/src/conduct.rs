@@ -0,0 +1,184 @@
+use common_game::utils::ID;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Abusive patterns the planet watches for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Offence {
+    Spam,           // Too many messages within the spam window
+    InvalidRequest, // Request for a resource or a recipe the planet doesn't support
+    Unregistered,   // Message from an explorer that never arrived on the planet
+}
+
+impl Offence {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Offence::Spam => "spam",
+            Offence::InvalidRequest => "invalid_request",
+            Offence::Unregistered => "unregistered",
+        }
+    }
+}
+
+/// Scoring and blacklisting rules
+#[derive(Debug, Clone)]
+pub struct ConductConfig {
+    pub spam_messages: Option<u32>, // More messages than this within `spam_window` count as spam
+    pub spam_window: Duration,
+    pub spam_penalty: u32,
+    pub invalid_request_penalty: u32,
+    pub unregistered_penalty: u32,
+    pub offence_window: Duration,       // Offences older than this no longer count towards the score
+    pub ban_threshold: Option<u32>,     // Score at which an explorer is blacklisted, `None` never bans
+    pub ban_duration: Option<Duration>, // `None` makes every ban permanent
+    pub permanent_after: Option<u32>,   // Number of temporary bans after which the next one is permanent
+}
+
+impl Default for ConductConfig {
+    fn default() -> Self {
+        Self {
+            spam_messages: None,
+            spam_window: Duration::from_secs(1),
+            spam_penalty: 1,
+            invalid_request_penalty: 2,
+            unregistered_penalty: 5,
+            offence_window: Duration::from_secs(60),
+            ban_threshold: None,
+            ban_duration: Some(Duration::from_secs(30)),
+            permanent_after: Some(3),
+        }
+    }
+}
+
+/// Blacklisting state of an explorer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ban {
+    Temporary { until: Instant },
+    Permanent,
+}
+
+/// Conduct of a single explorer, as seen by the host
+#[derive(Debug, Clone, Default)]
+pub struct ConductRecord {
+    pub score: u32,                      // Penalties of the offences within the offence window
+    pub offences: HashMap<Offence, u64>, // Offences since the explorer first showed up
+    pub bans: u32,
+    pub ban: Option<Ban>,
+    recent: VecDeque<(Instant, u32)>, // Penalties within the offence window
+    messages: VecDeque<Instant>,      // Messages within the spam window
+}
+
+impl ConductRecord {
+    pub fn is_banned(&self, now: Instant) -> bool {
+        match self.ban {
+            Some(Ban::Permanent) => true,
+            Some(Ban::Temporary { until }) => now < until,
+            None => false,
+        }
+    }
+}
+
+/// Scores the explorers' behaviour and keeps the blacklist
+#[derive(Debug, Default)]
+pub struct ConductTracker {
+    config: ConductConfig,
+    explorers: HashMap<ID, ConductRecord>,
+}
+
+impl ConductTracker {
+    pub fn new(config: ConductConfig) -> Self {
+        Self {
+            config,
+            explorers: HashMap::new(),
+        }
+    }
+
+    pub fn is_banned(&self, explorer_id: ID, now: Instant) -> bool {
+        self.explorers
+            .get(&explorer_id)
+            .is_some_and(|record| record.is_banned(now))
+    }
+
+    // Counts a message for spam detection, returning true if it makes the explorer a spammer
+    pub fn record_message(&mut self, explorer_id: ID, now: Instant) -> bool {
+        let record = self.explorers.entry(explorer_id).or_default();
+        while record
+            .messages
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) >= self.config.spam_window)
+        {
+            record.messages.pop_front();
+        }
+        record.messages.push_back(now);
+
+        self.config
+            .spam_messages
+            .is_some_and(|max| record.messages.len() > max as usize)
+    }
+
+    // Adds an offence to the explorer score. Returns the ban it triggered, if any.
+    pub fn record_offence(&mut self, explorer_id: ID, offence: Offence, now: Instant) -> Option<Ban> {
+        let penalty = match offence {
+            Offence::Spam => self.config.spam_penalty,
+            Offence::InvalidRequest => self.config.invalid_request_penalty,
+            Offence::Unregistered => self.config.unregistered_penalty,
+        };
+
+        let record = self.explorers.entry(explorer_id).or_default();
+        *record.offences.entry(offence).or_default() += 1;
+        while record
+            .recent
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) >= self.config.offence_window)
+        {
+            record.recent.pop_front();
+        }
+        record.recent.push_back((now, penalty));
+        record.score = record.recent.iter().map(|(_, penalty)| penalty).sum();
+
+        let threshold = self.config.ban_threshold?;
+        if record.score < threshold || record.is_banned(now) {
+            return None;
+        }
+
+        let permanent = self.config.ban_duration.is_none()
+            || self
+                .config
+                .permanent_after
+                .is_some_and(|limit| record.bans >= limit);
+        let ban = match self.config.ban_duration {
+            Some(duration) if !permanent => Ban::Temporary {
+                until: now + duration,
+            },
+            _ => Ban::Permanent,
+        };
+
+        // The score starts again from zero once the explorer is punished
+        record.bans += 1;
+        record.ban = Some(ban);
+        record.recent.clear();
+        record.score = 0;
+        Some(ban)
+    }
+
+    // Lifts the ban of an explorer, returning false if it wasn't banned
+    pub fn pardon(&mut self, explorer_id: ID) -> bool {
+        self.explorers
+            .get_mut(&explorer_id)
+            .and_then(|record| record.ban.take())
+            .is_some()
+    }
+
+    pub fn record(&self, explorer_id: ID) -> Option<&ConductRecord> {
+        self.explorers.get(&explorer_id)
+    }
+
+    // Explorers currently blacklisted, with their ban
+    pub fn blacklist(&self, now: Instant) -> HashMap<ID, Ban> {
+        self.explorers
+            .iter()
+            .filter(|(_, record)| record.is_banned(now))
+            .filter_map(|(explorer_id, record)| Some((*explorer_id, record.ban?)))
+            .collect()
+    }
+}
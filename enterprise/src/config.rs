@@ -0,0 +1,88 @@
+use crate::adaptive::AdaptiveConfig;
+use crate::assist::AssistConfig;
+use crate::auction::AuctionConfig;
+use crate::billing::BillingConfig;
+use crate::cache::CacheConfig;
+use crate::conduct::ConductConfig;
+use crate::directory::GalaxyDirectory;
+use crate::postmortem::PostMortemConfig;
+use crate::priority::PriorityClass;
+use crate::quota::QuotaConfig;
+use crate::risk::RiskConfig;
+use crate::sunrays::SunrayModelConfig;
+use crate::waitlist::WaitlistConfig;
+use common_game::utils::ID;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Tunable parameters of the Enterprise AI
+#[derive(Debug, Clone)]
+pub struct EnterpriseConfig {
+    // Explorers that send nothing for longer than this are considered gone.
+    // `None`, the default, keeps every explorer until the orchestrator reports its departure.
+    pub explorer_timeout: Option<Duration>,
+    // Explorers that haven't asked for energy within this window don't stop the planet from
+    // building rockets. `None` saves energy for any explorer on the planet.
+    pub demand_window: Option<Duration>,
+    // Time horizon over which the explorer demand is predicted
+    pub demand_horizon: Duration,
+    // If the predicted probability that an explorer on the planet asks for energy within the
+    // demand horizon reaches this value, idle explorers still get their energy saved
+    pub demand_forecast_threshold: Option<f64>,
+    // Limits on the energy and the messages of every explorer
+    pub quota: QuotaConfig,
+    // How misbehaving explorers are scored and blacklisted
+    pub conduct: ConductConfig,
+    // Planets explorers are referred to for the resources Enterprise can't provide.
+    // The host can update it at runtime through the handle.
+    pub directory: GalaxyDirectory,
+    // Maximum Carbon made with idle energy when nobody is on the planet and a rocket is ready.
+    // `None` lets those sunrays go wasted.
+    pub carbon_stockpile: Option<usize>,
+    // Recipes for which the planet supplies stockpiled Carbon to the explorers
+    pub assist: AssistConfig,
+    // Complex resources pre-crafted with idle energy, served before using a live cell
+    pub cache: CacheConfig,
+    // Explorers bidding for the next charged cell
+    pub auction: AuctionConfig,
+    // Priority class of the explorers, the ones not listed are Normal.
+    // The host can change them at runtime through the handle.
+    pub priorities: HashMap<ID, PriorityClass>,
+    // Credits charged for each resource and budget of the explorers
+    pub billing: BillingConfig,
+    // Generation requests made without energy, fulfilled at the next sunray
+    pub waitlist: WaitlistConfig,
+    // How the model of the sunray arrivals steers the energy policy
+    pub sunray_model: SunrayModelConfig,
+    // Self-tuning mode, in which the planet learns which energy policy suits the game
+    pub adaptive: AdaptiveConfig,
+    // How the risk of being hit by an asteroid without defense is scored
+    pub risk: RiskConfig,
+    // Report sent to the host when an asteroid destroys the planet
+    pub post_mortem: PostMortemConfig,
+}
+
+impl Default for EnterpriseConfig {
+    fn default() -> Self {
+        Self {
+            explorer_timeout: None,
+            demand_window: None,
+            demand_horizon: Duration::from_secs(30),
+            demand_forecast_threshold: None,
+            quota: QuotaConfig::default(),
+            conduct: ConductConfig::default(),
+            directory: GalaxyDirectory::default(),
+            carbon_stockpile: None,
+            assist: AssistConfig::default(),
+            cache: CacheConfig::default(),
+            auction: AuctionConfig::default(),
+            priorities: HashMap::new(),
+            billing: BillingConfig::default(),
+            waitlist: WaitlistConfig::default(),
+            sunray_model: SunrayModelConfig::default(),
+            adaptive: AdaptiveConfig::default(),
+            risk: RiskConfig::default(),
+            post_mortem: PostMortemConfig::default(),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use common_game::utils::ID;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Weight of the newest interval in the smoothed interval between energy requests
+const INTERVAL_SMOOTHING: f64 = 0.3;
+// Shortest interval considered, so that a burst of requests doesn't produce an infinite rate
+const MIN_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Explorer requests that tell us something about the energy demand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DemandKind {
+    Generate,    // GenerateResourceRequest, consumes a charged cell
+    Combine,     // CombineResourceRequest, consumes a charged cell
+    EnergyQuery, // AvailableEnergyCellRequest, the explorer is thinking about consuming one
+}
+
+/// Request history of one explorer (or of the whole planet)
+#[derive(Debug, Clone, Default)]
+pub struct DemandStats {
+    pub generate_requests: u64,
+    pub combine_requests: u64,
+    pub energy_queries: u64,
+    pub first_request: Option<Instant>,
+    pub last_request: Option<Instant>,        // Last request of any kind
+    pub last_energy_request: Option<Instant>, // Last request that consumes a charged cell
+    pub mean_interval: Option<Duration>,      // Smoothed interval between energy requests
+}
+
+impl DemandStats {
+    pub fn record(&mut self, kind: DemandKind, now: Instant) {
+        match kind {
+            DemandKind::Generate => self.generate_requests += 1,
+            DemandKind::Combine => self.combine_requests += 1,
+            DemandKind::EnergyQuery => self.energy_queries += 1,
+        }
+        self.first_request.get_or_insert(now);
+        self.last_request = Some(now);
+
+        if kind == DemandKind::EnergyQuery {
+            return;
+        }
+        if let Some(previous) = self.last_energy_request {
+            let interval = now.saturating_duration_since(previous).max(MIN_INTERVAL);
+            self.mean_interval = Some(match self.mean_interval {
+                Some(mean) => mean.mul_f64(1.0 - INTERVAL_SMOOTHING) + interval.mul_f64(INTERVAL_SMOOTHING),
+                None => interval,
+            });
+        }
+        self.last_energy_request = Some(now);
+    }
+
+    pub fn energy_requests(&self) -> u64 {
+        self.generate_requests + self.combine_requests
+    }
+
+    // Estimated energy requests per second.
+    // The rate decays once the silence gets longer than the usual interval between requests.
+    pub fn rate(&self, now: Instant) -> f64 {
+        let Some(last) = self.last_energy_request else {
+            return 0.0;
+        };
+        let since_last = now.saturating_duration_since(last);
+        let interval = match self.mean_interval {
+            Some(mean) => mean.max(since_last),
+            None => since_last,
+        };
+        1.0 / interval.max(MIN_INTERVAL).as_secs_f64()
+    }
+
+    pub fn forecast(&self, now: Instant, horizon: Duration) -> DemandForecast {
+        let rate = self.rate(now);
+        let expected = rate * horizon.as_secs_f64();
+        DemandForecast {
+            rate_per_sec: rate,
+            expected_requests: expected,
+            // Requests are treated as a Poisson process
+            probability: 1.0 - (-expected).exp(),
+            since_last_request: self.last_energy_request.map(|at| now.saturating_duration_since(at)),
+        }
+    }
+}
+
+/// Predicted energy demand over a time horizon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DemandForecast {
+    pub rate_per_sec: f64,
+    pub expected_requests: f64,
+    pub probability: f64, // Probability of at least one energy request within the horizon
+    pub since_last_request: Option<Duration>,
+}
+
+impl DemandForecast {
+    // Forecast of several independent sources (explorers) combined
+    pub fn combine(forecasts: impl IntoIterator<Item = DemandForecast>) -> DemandForecast {
+        let mut combined = DemandForecast::default();
+        let mut none_probability = 1.0;
+        for forecast in forecasts {
+            combined.rate_per_sec += forecast.rate_per_sec;
+            combined.expected_requests += forecast.expected_requests;
+            none_probability *= 1.0 - forecast.probability;
+            combined.since_last_request = match (combined.since_last_request, forecast.since_last_request) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+        combined.probability = 1.0 - none_probability;
+        combined
+    }
+}
+
+impl Default for DemandForecast {
+    fn default() -> Self {
+        Self {
+            rate_per_sec: 0.0,
+            expected_requests: 0.0,
+            probability: 0.0,
+            since_last_request: None,
+        }
+    }
+}
+
+/// Energy demand model built from the explorer requests, both per explorer and for the whole planet
+#[derive(Debug, Clone, Default)]
+pub struct DemandModel {
+    pub global: DemandStats,
+    pub per_explorer: HashMap<ID, DemandStats>,
+}
+
+impl DemandModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, explorer_id: ID, kind: DemandKind, now: Instant) {
+        self.global.record(kind, now);
+        self.per_explorer.entry(explorer_id).or_default().record(kind, now);
+    }
+
+    pub fn explorer(&self, explorer_id: ID) -> Option<&DemandStats> {
+        self.per_explorer.get(&explorer_id)
+    }
+
+    // Forecast for a single explorer; explorers we never heard from have no demand
+    pub fn forecast(&self, explorer_id: ID, now: Instant, horizon: Duration) -> DemandForecast {
+        self.per_explorer
+            .get(&explorer_id)
+            .map(|stats| stats.forecast(now, horizon))
+            .unwrap_or_default()
+    }
+
+    // Combined forecast for a group of explorers (usually the ones on the planet)
+    pub fn forecast_for<'a>(
+        &self,
+        explorers: impl IntoIterator<Item = &'a ID>,
+        now: Instant,
+        horizon: Duration,
+    ) -> DemandForecast {
+        DemandForecast::combine(
+            explorers
+                .into_iter()
+                .map(|explorer_id| self.forecast(*explorer_id, now, horizon)),
+        )
+    }
+}
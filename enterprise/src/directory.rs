@@ -0,0 +1,86 @@
+use common_game::components::resource::ResourceType;
+use common_game::utils::ID;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+// Referrals kept for the host, the oldest are dropped first
+const MAX_REFERRALS: usize = 256;
+
+/// Local directory of the planets that provide each resource, registered by the host
+#[derive(Debug, Clone, Default)]
+pub struct GalaxyDirectory {
+    providers: HashMap<ResourceType, Vec<ID>>, // Planets in order of preference
+}
+
+impl GalaxyDirectory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds a planet at the end of the providers of the resource
+    pub fn register(&mut self, resource: ResourceType, planet_id: ID) {
+        let providers = self.providers.entry(resource).or_default();
+        if !providers.contains(&planet_id) {
+            providers.push(planet_id);
+        }
+    }
+
+    // Removes a planet from the providers of the resource, returns false if it wasn't registered
+    pub fn unregister(&mut self, resource: ResourceType, planet_id: ID) -> bool {
+        let Some(providers) = self.providers.get_mut(&resource) else {
+            return false;
+        };
+        let before = providers.len();
+        providers.retain(|id| *id != planet_id);
+        before != providers.len()
+    }
+
+    // Removes a planet from every resource, e.g. when it gets destroyed
+    pub fn remove_planet(&mut self, planet_id: ID) {
+        for providers in self.providers.values_mut() {
+            providers.retain(|id| *id != planet_id);
+        }
+    }
+
+    pub fn providers(&self, resource: ResourceType) -> &[ID] {
+        self.providers.get(&resource).map_or(&[], Vec::as_slice)
+    }
+
+    // The preferred planet for the resource
+    pub fn best_next_hop(&self, resource: ResourceType) -> Option<ID> {
+        self.providers(resource).first().copied()
+    }
+}
+
+/// Where the planet sent an explorer asking for an unsupported resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Referral {
+    pub explorer_id: ID,
+    pub resource: ResourceType,
+    pub planet_id: Option<ID>, // `None` if no planet in the directory provides the resource
+    pub at: Instant,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Referrals {
+    recent: VecDeque<Referral>,
+    latest: HashMap<ID, Referral>, // Latest referral of each explorer
+}
+
+impl Referrals {
+    pub(crate) fn record(&mut self, referral: Referral) {
+        if self.recent.len() == MAX_REFERRALS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(referral);
+        self.latest.insert(referral.explorer_id, referral);
+    }
+
+    pub(crate) fn recent(&self) -> Vec<Referral> {
+        self.recent.iter().copied().collect()
+    }
+
+    pub(crate) fn latest(&self, explorer_id: ID) -> Option<Referral> {
+        self.latest.get(&explorer_id).copied()
+    }
+}
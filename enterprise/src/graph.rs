@@ -0,0 +1,141 @@
+use crate::recipes::{recipe_inputs, resource_name, Recipes};
+use common_game::components::resource::ResourceType;
+use std::collections::BTreeMap;
+
+/// Resource in the recipe graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeNode {
+    pub resource: ResourceType,
+    pub producible: bool, // The planet can generate or combine it itself
+}
+
+/// Input of a combination recipe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeEdge {
+    pub input: ResourceType,
+    pub output: ResourceType,
+    pub quantity: u32, // Diamond takes two Carbon
+}
+
+/// Generation and combination rules of a planet, as a graph from the inputs to the outputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeGraph {
+    pub nodes: Vec<RecipeNode>, // Sorted by name
+    pub edges: Vec<RecipeEdge>,
+}
+
+fn kind(resource: ResourceType) -> &'static str {
+    match resource {
+        ResourceType::Basic(_) => "basic",
+        ResourceType::Complex(_) => "complex",
+    }
+}
+
+impl RecipeGraph {
+    pub fn from_recipes(recipes: &Recipes) -> Self {
+        // Keyed by name so that the exports are stable
+        let mut nodes = BTreeMap::new();
+        let mut edges = BTreeMap::new();
+
+        for basic in &recipes.generation {
+            let resource = ResourceType::Basic(*basic);
+            nodes.insert(resource_name(resource), RecipeNode { resource, producible: true });
+        }
+        for complex in &recipes.combination {
+            let output = ResourceType::Complex(*complex);
+            nodes.insert(resource_name(output), RecipeNode { resource: output, producible: true });
+
+            let (a, b) = recipe_inputs(*complex);
+            for input in [a, b] {
+                nodes.entry(resource_name(input)).or_insert(RecipeNode {
+                    resource: input,
+                    producible: false,
+                });
+                edges
+                    .entry((resource_name(input), resource_name(output)))
+                    .or_insert(RecipeEdge { input, output, quantity: 0 })
+                    .quantity += 1;
+            }
+        }
+
+        Self {
+            nodes: nodes.into_values().collect(),
+            edges: edges.into_values().collect(),
+        }
+    }
+
+    // Graph of the recipes Enterprise is created with
+    pub fn enterprise() -> Self {
+        Self::from_recipes(&Recipes::enterprise())
+    }
+
+    // Graphviz export, producible resources are filled
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph recipes {\n    rankdir=LR;\n");
+        for node in &self.nodes {
+            let shape = match node.resource {
+                ResourceType::Basic(_) => "ellipse",
+                ResourceType::Complex(_) => "box",
+            };
+            let style = if node.producible {
+                ", style=filled, fillcolor=lightblue"
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "    \"{}\" [shape={}{}];\n",
+                resource_name(node.resource),
+                shape,
+                style
+            ));
+        }
+        for edge in &self.edges {
+            let label = if edge.quantity > 1 {
+                format!(" [label=\"x{}\"]", edge.quantity)
+            } else {
+                String::new()
+            };
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\"{};\n",
+                resource_name(edge.input),
+                resource_name(edge.output),
+                label
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_json(&self) -> String {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                format!(
+                    "    {{\"id\": \"{}\", \"kind\": \"{}\", \"producible\": {}}}",
+                    resource_name(node.resource),
+                    kind(node.resource),
+                    node.producible
+                )
+            })
+            .collect::<Vec<_>>();
+        let edges = self
+            .edges
+            .iter()
+            .map(|edge| {
+                format!(
+                    "    {{\"from\": \"{}\", \"to\": \"{}\", \"quantity\": {}}}",
+                    resource_name(edge.input),
+                    resource_name(edge.output),
+                    edge.quantity
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\n  \"nodes\": [\n{}\n  ],\n  \"edges\": [\n{}\n  ]\n}}\n",
+            nodes.join(",\n"),
+            edges.join(",\n")
+        )
+    }
+}
@@ -0,0 +1,350 @@
+use crate::adaptive::{EnergyPolicy, PolicyLearner, PolicyStats};
+use crate::assist::{AssistBook, AssistOrder, AssistUsage, AssistedCraft};
+use crate::auction::{AuctionBook, AuctionResult};
+use crate::billing::{Account, BillingBook};
+use crate::cache::CacheStats;
+use crate::conduct::{Ban, ConductRecord, ConductTracker};
+use crate::config::EnterpriseConfig;
+use crate::demand::{DemandForecast, DemandModel};
+use crate::directory::{GalaxyDirectory, Referral, Referrals};
+use crate::graph::RecipeGraph;
+use crate::ledger::{Ledger, Transaction};
+use crate::planner::{CraftPlan, CraftPlanner, PlanError};
+use crate::priority::{ClassStats, PriorityBook, PriorityClass};
+use crate::quota::{QuotaTracker, QuotaUsage};
+use crate::quote::{quote, Quote, QuoteRequest};
+use crate::recipes::Recipes;
+use crate::risk::{RiskAssessment, RiskModel};
+use crate::sunrays::{SunrayHistory, SunrayPattern};
+use crate::unsupported::UnsupportedDemandReport;
+use common_game::components::resource::{ComplexResourceType, GenericResource, ResourceType};
+use common_game::utils::ID;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+// State shared between the AI (running in the planet thread) and the host
+#[derive(Debug)]
+pub(crate) struct SharedState {
+    pub(crate) demand: DemandModel,
+    pub(crate) quotas: QuotaTracker,
+    pub(crate) conduct: ConductTracker,
+    pub(crate) unsupported: UnsupportedDemandReport,
+    pub(crate) directory: GalaxyDirectory,
+    pub(crate) referrals: Referrals,
+    pub(crate) recipes: Recipes,
+    pub(crate) charged_cell: bool, // As of the last event handled by the planet
+    pub(crate) stockpiled_carbon: usize,
+    pub(crate) assists: AssistBook,
+    pub(crate) cache: CacheStats,
+    pub(crate) auction: AuctionBook,
+    pub(crate) priorities: PriorityBook,
+    pub(crate) ledger: Ledger,
+    pub(crate) billing: BillingBook,
+    pub(crate) sunrays: SunrayHistory,
+    pub(crate) learner: PolicyLearner,
+    pub(crate) risk: RiskModel,
+}
+
+impl SharedState {
+    pub(crate) fn new(config: &EnterpriseConfig) -> Self {
+        Self {
+            demand: DemandModel::new(),
+            quotas: QuotaTracker::new(config.quota.clone()),
+            conduct: ConductTracker::new(config.conduct.clone()),
+            unsupported: UnsupportedDemandReport::new(),
+            directory: config.directory.clone(),
+            referrals: Referrals::default(),
+            recipes: Recipes::enterprise(),
+            charged_cell: false,
+            stockpiled_carbon: 0,
+            assists: AssistBook::new(config.assist.clone()),
+            cache: CacheStats::default(),
+            auction: AuctionBook::new(config.auction.clone()),
+            priorities: PriorityBook::new(config.priorities.clone()),
+            ledger: Ledger::new(Instant::now()),
+            billing: BillingBook::new(config.billing.clone()),
+            sunrays: SunrayHistory::default(),
+            learner: PolicyLearner::new(config.adaptive.clone()),
+            risk: RiskModel::new(config.risk.clone()),
+        }
+    }
+}
+
+pub(crate) type Shared = Arc<Mutex<SharedState>>;
+
+// The AI must keep working even if a host thread panicked while holding the lock
+pub(crate) fn lock(shared: &Shared) -> MutexGuard<'_, SharedState> {
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Handle used by the host to inspect (and steer) an Enterprise AI while the planet is running
+#[derive(Debug, Clone)]
+pub struct EnterpriseHandle {
+    shared: Shared,
+}
+
+impl EnterpriseHandle {
+    pub(crate) fn new(shared: Shared) -> Self {
+        Self { shared }
+    }
+
+    /// Snapshot of the explorer demand model
+    pub fn demand_model(&self) -> DemandModel {
+        lock(&self.shared).demand.clone()
+    }
+
+    /// Predicted energy demand of an explorer over the given horizon
+    pub fn predicted_demand(&self, explorer_id: ID, horizon: Duration) -> DemandForecast {
+        lock(&self.shared)
+            .demand
+            .forecast(explorer_id, Instant::now(), horizon)
+    }
+
+    /// Quota usage of an explorer, `None` if the explorer never sent anything
+    pub fn quota_usage(&self, explorer_id: ID) -> Option<QuotaUsage> {
+        lock(&self.shared).quotas.usage(explorer_id, Instant::now())
+    }
+
+    /// Quota usage of every explorer that sent at least one message
+    pub fn quota_usages(&self) -> HashMap<ID, QuotaUsage> {
+        let shared = lock(&self.shared);
+        let now = Instant::now();
+        shared
+            .quotas
+            .explorers()
+            .filter_map(|explorer_id| Some((*explorer_id, shared.quotas.usage(*explorer_id, now)?)))
+            .collect()
+    }
+
+    /// Behaviour score, offences and bans of an explorer
+    pub fn conduct(&self, explorer_id: ID) -> Option<ConductRecord> {
+        lock(&self.shared).conduct.record(explorer_id).cloned()
+    }
+
+    /// Explorers currently blacklisted
+    pub fn blacklist(&self) -> HashMap<ID, Ban> {
+        lock(&self.shared).conduct.blacklist(Instant::now())
+    }
+
+    /// Lifts the ban of an explorer, returns false if it wasn't banned
+    pub fn pardon(&self, explorer_id: ID) -> bool {
+        lock(&self.shared).conduct.pardon(explorer_id)
+    }
+
+    /// Which unsupported resources the explorers asked for, how often and who asked
+    pub fn unsupported_demand(&self) -> UnsupportedDemandReport {
+        lock(&self.shared).unsupported.clone()
+    }
+
+    /// Registers a planet that provides a resource, after the ones already registered for it
+    pub fn register_provider(&self, resource: ResourceType, planet_id: ID) {
+        lock(&self.shared).directory.register(resource, planet_id);
+    }
+
+    /// Removes a planet from the providers of a resource, returns false if it wasn't registered
+    pub fn unregister_provider(&self, resource: ResourceType, planet_id: ID) -> bool {
+        lock(&self.shared).directory.unregister(resource, planet_id)
+    }
+
+    /// Removes a planet from the directory, e.g. after it got destroyed
+    pub fn remove_planet(&self, planet_id: ID) {
+        lock(&self.shared).directory.remove_planet(planet_id);
+    }
+
+    /// Replaces the whole galaxy directory
+    pub fn set_directory(&self, directory: GalaxyDirectory) {
+        lock(&self.shared).directory = directory;
+    }
+
+    /// Snapshot of the galaxy directory
+    pub fn directory(&self) -> GalaxyDirectory {
+        lock(&self.shared).directory.clone()
+    }
+
+    /// The preferred planet to ask for a resource
+    pub fn best_next_hop(&self, resource: ResourceType) -> Option<ID> {
+        lock(&self.shared).directory.best_next_hop(resource)
+    }
+
+    /// Latest referral given to an explorer
+    pub fn referral(&self, explorer_id: ID) -> Option<Referral> {
+        lock(&self.shared).referrals.latest(explorer_id)
+    }
+
+    /// Most recent referrals, oldest first
+    pub fn referrals(&self) -> Vec<Referral> {
+        lock(&self.shared).referrals.recent()
+    }
+
+    /// Evaluates a generation or combination request without consuming anything
+    pub fn quote(&self, request: QuoteRequest) -> Quote {
+        let shared = lock(&self.shared);
+        quote(
+            request,
+            &shared.recipes,
+            shared.charged_cell,
+            shared.stockpiled_carbon,
+        )
+    }
+
+    /// Generation and combination recipes enabled on the planet
+    pub fn recipes(&self) -> Recipes {
+        lock(&self.shared).recipes.clone()
+    }
+
+    /// Carbon currently held in the stockpile
+    pub fn stockpiled_carbon(&self) -> usize {
+        lock(&self.shared).stockpiled_carbon
+    }
+
+    /// Orders a craft in which the planet supplies the missing Carbon from its stockpile.
+    /// The inputs are given back right away if the planet doesn't assist with the recipe.
+    pub fn order_assisted_craft(
+        &self,
+        explorer_id: ID,
+        resource: ComplexResourceType,
+        provided: Vec<GenericResource>,
+    ) -> Result<(), Vec<GenericResource>> {
+        lock(&self.shared).assists.order(AssistOrder {
+            explorer_id,
+            resource,
+            provided,
+        })
+    }
+
+    /// Takes the completed (or rejected) assisted crafts of an explorer
+    pub fn take_assisted_crafts(&self, explorer_id: ID) -> Vec<AssistedCraft> {
+        lock(&self.shared).assists.take(explorer_id)
+    }
+
+    /// Carbon supplied to an explorer through assisted crafting
+    pub fn assist_usage(&self, explorer_id: ID) -> Option<AssistUsage> {
+        lock(&self.shared).assists.usage(explorer_id)
+    }
+
+    /// Generation and combination steps the planet needs to craft the target from an inventory
+    pub fn plan_craft(
+        &self,
+        target: ResourceType,
+        inventory: &HashMap<ResourceType, u32>,
+    ) -> Result<CraftPlan, PlanError> {
+        CraftPlanner::new(self.recipes()).plan(target, inventory)
+    }
+
+    /// Recipe graph of the planet, exportable to DOT and JSON
+    pub fn recipe_graph(&self) -> RecipeGraph {
+        RecipeGraph::from_recipes(&lock(&self.shared).recipes)
+    }
+
+    /// Pre-crafted resources held by the planet and the cache activity
+    pub fn cache_stats(&self) -> CacheStats {
+        lock(&self.shared).cache.clone()
+    }
+
+    /// Bids for the next charged cell with a priority and resources paid if the bid wins.
+    /// The resources are given back right away if auctions are disabled.
+    pub fn bid(
+        &self,
+        explorer_id: ID,
+        priority: u32,
+        offered: Vec<GenericResource>,
+    ) -> Result<(), Vec<GenericResource>> {
+        lock(&self.shared)
+            .auction
+            .bid(explorer_id, priority, offered, Instant::now())
+    }
+
+    /// Resources of the losing or replaced bids of an explorer
+    pub fn take_refunds(&self, explorer_id: ID) -> Vec<GenericResource> {
+        lock(&self.shared).auction.take_refunds(explorer_id)
+    }
+
+    /// Explorer the next charged cell is reserved for
+    pub fn cell_reserved_for(&self) -> Option<ID> {
+        lock(&self.shared).auction.reserved_for(Instant::now())
+    }
+
+    /// Results of the auctions settled so far
+    pub fn auction_results(&self) -> Vec<AuctionResult> {
+        lock(&self.shared).auction.results()
+    }
+
+    /// Assigns an explorer to a priority class
+    pub fn set_priority(&self, explorer_id: ID, class: PriorityClass) {
+        lock(&self.shared).priorities.set(explorer_id, class);
+    }
+
+    /// Priority class of an explorer
+    pub fn priority(&self, explorer_id: ID) -> PriorityClass {
+        lock(&self.shared).priorities.class_of(explorer_id)
+    }
+
+    /// Cells served and requests refused for each priority class
+    pub fn priority_stats(&self) -> HashMap<PriorityClass, ClassStats> {
+        lock(&self.shared).priorities.stats()
+    }
+
+    /// Snapshot of the transaction ledger, exportable to CSV and JSON
+    pub fn ledger(&self) -> Ledger {
+        lock(&self.shared).ledger.clone()
+    }
+
+    /// Transactions handled for an explorer, oldest first
+    pub fn transactions(&self, explorer_id: ID) -> Vec<Transaction> {
+        lock(&self.shared).ledger.for_explorer(explorer_id)
+    }
+
+    /// Account of an explorer: credits spent, budget and charges for each resource
+    pub fn account(&self, explorer_id: ID) -> Account {
+        lock(&self.shared).billing.account(explorer_id)
+    }
+
+    /// Overrides the budget of an explorer, `None` lets it spend without limits
+    pub fn set_budget(&self, explorer_id: ID, budget: Option<u64>) {
+        lock(&self.shared).billing.set_budget(explorer_id, budget);
+    }
+
+    /// Credits charged for a resource
+    pub fn cost(&self, resource: ResourceType) -> u64 {
+        lock(&self.shared).billing.cost(resource)
+    }
+
+    /// Bills of every explorer charged during the game
+    pub fn bills(&self) -> HashMap<ID, Account> {
+        lock(&self.shared).billing.bills()
+    }
+
+    /// Estimated time before a charged cell is available, zero if one is charged right now.
+    /// `None` until two sunrays were observed.
+    pub fn energy_eta(&self) -> Option<Duration> {
+        let shared = lock(&self.shared);
+        shared.sunrays.energy_eta(shared.charged_cell, Instant::now())
+    }
+
+    /// Mean time between two sunrays observed by the planet
+    pub fn sunray_interval(&self) -> Option<Duration> {
+        lock(&self.shared).sunrays.mean_interval()
+    }
+
+    /// Rate, burstiness and cycles of the sunray arrivals, with the confidence of the model.
+    /// `None` until three sunrays were observed.
+    pub fn sunray_pattern(&self) -> Option<SunrayPattern> {
+        lock(&self.shared).sunrays.pattern()
+    }
+
+    /// Energy policy currently played by the self-tuning mode, `Balanced` when it is disabled
+    pub fn energy_policy(&self) -> EnergyPolicy {
+        lock(&self.shared).learner.policy()
+    }
+
+    /// Epochs played and mean reward of each policy of the self-tuning mode
+    pub fn policy_stats(&self) -> Vec<PolicyStats> {
+        lock(&self.shared).learner.stats()
+    }
+
+    /// Latest risk assessment, made at the last sunray or request for energy
+    pub fn risk(&self) -> Option<RiskAssessment> {
+        lock(&self.shared).risk.last()
+    }
+}
@@ -0,0 +1,200 @@
+use crate::recipes::resource_name;
+use common_game::components::resource::ResourceType;
+use common_game::utils::ID;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+// Transactions kept for the host, the oldest are dropped first
+const MAX_TRANSACTIONS: usize = 8192;
+
+/// What the explorer asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransactionKind {
+    Generate,
+    Combine,
+}
+
+impl TransactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionKind::Generate => "generate",
+            TransactionKind::Combine => "combine",
+        }
+    }
+}
+
+/// How the planet answered, and where the resource came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionOutcome {
+    Generated,      // Made with a charged cell
+    FromStockpile,  // Carbon made earlier with idle energy
+    FromCache,      // Resource pre-crafted earlier with idle energy
+    Failed(String), // Nothing was handed out, with the reason
+}
+
+impl TransactionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionOutcome::Generated => "generated",
+            TransactionOutcome::FromStockpile => "from_stockpile",
+            TransactionOutcome::FromCache => "from_cache",
+            TransactionOutcome::Failed(_) => "failed",
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        !matches!(self, TransactionOutcome::Failed(_))
+    }
+}
+
+/// A generation or combination handled for an explorer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub at: Instant,
+    pub explorer_id: ID,
+    pub kind: TransactionKind,
+    pub inputs: Vec<ResourceType>,    // Resources given by the explorer
+    pub output: Option<ResourceType>, // Resource handed out, `None` if the request failed
+    pub energy_cells: u32,            // Charged cells consumed
+    pub outcome: TransactionOutcome,
+}
+
+/// Every generation and combination handled by the planet, used to audit how the energy was spent
+#[derive(Debug, Clone)]
+pub struct Ledger {
+    started_at: Instant, // Timestamps are exported relative to it
+    transactions: VecDeque<Transaction>,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+// Quotes a CSV field if needed
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn json_string(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len() + 2);
+    escaped.push('"');
+    for c in field.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+impl Ledger {
+    pub fn new(started_at: Instant) -> Self {
+        Self {
+            started_at,
+            transactions: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, transaction: Transaction) {
+        if self.transactions.len() == MAX_TRANSACTIONS {
+            self.transactions.pop_front();
+        }
+        self.transactions.push_back(transaction);
+    }
+
+    pub fn transactions(&self) -> impl Iterator<Item = &Transaction> {
+        self.transactions.iter()
+    }
+
+    pub fn for_explorer(&self, explorer_id: ID) -> Vec<Transaction> {
+        self.transactions
+            .iter()
+            .filter(|transaction| transaction.explorer_id == explorer_id)
+            .cloned()
+            .collect()
+    }
+
+    // Charged cells consumed on behalf of the explorer
+    pub fn energy_spent(&self, explorer_id: ID) -> u32 {
+        self.transactions
+            .iter()
+            .filter(|transaction| transaction.explorer_id == explorer_id)
+            .map(|transaction| transaction.energy_cells)
+            .sum()
+    }
+
+    fn elapsed_ms(&self, transaction: &Transaction) -> u128 {
+        transaction.at.saturating_duration_since(self.started_at).as_millis()
+    }
+
+    // One line per transaction, the inputs are separated by `;`
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("elapsed_ms,explorer_id,kind,inputs,output,energy_cells,outcome,error\n");
+        for transaction in &self.transactions {
+            let inputs = transaction.inputs.iter().map(|r| resource_name(*r)).collect::<Vec<_>>();
+            let error = match &transaction.outcome {
+                TransactionOutcome::Failed(reason) => reason.as_str(),
+                _ => "",
+            };
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                self.elapsed_ms(transaction),
+                transaction.explorer_id,
+                transaction.kind.as_str(),
+                csv_field(&inputs.join(";")),
+                transaction.output.map(resource_name).unwrap_or_default(),
+                transaction.energy_cells,
+                transaction.outcome.as_str(),
+                csv_field(error)
+            ));
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let transactions = self
+            .transactions
+            .iter()
+            .map(|transaction| {
+                let inputs = transaction
+                    .inputs
+                    .iter()
+                    .map(|r| json_string(&resource_name(*r)))
+                    .collect::<Vec<_>>();
+                let output = transaction
+                    .output
+                    .map_or("null".to_string(), |r| json_string(&resource_name(r)));
+                let error = match &transaction.outcome {
+                    TransactionOutcome::Failed(reason) => json_string(reason),
+                    _ => "null".to_string(),
+                };
+                format!(
+                    "    {{\"elapsed_ms\": {}, \"explorer_id\": {}, \"kind\": \"{}\", \"inputs\": [{}], \"output\": {}, \"energy_cells\": {}, \"outcome\": \"{}\", \"error\": {}}}",
+                    self.elapsed_ms(transaction),
+                    transaction.explorer_id,
+                    transaction.kind.as_str(),
+                    inputs.join(", "),
+                    output,
+                    transaction.energy_cells,
+                    transaction.outcome.as_str(),
+                    error
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if transactions.is_empty() {
+            return "{\n  \"transactions\": []\n}\n".to_string();
+        }
+        format!("{{\n  \"transactions\": [\n{}\n  ]\n}}\n", transactions.join(",\n"))
+    }
+}
@@ -1,5 +1,60 @@
-#[allow(unused_imports)]
+#[allow(unused_imports, clippy::module_inception, clippy::assertions_on_constants)]
 mod tests;
+mod adaptive;
+mod assist;
+mod auction;
+mod billing;
+mod cache;
+mod conduct;
+mod config;
+mod demand;
+mod directory;
+mod graph;
+mod handle;
+mod ledger;
+mod planner;
+mod policy;
+mod postmortem;
+mod priority;
+mod quota;
+mod quote;
+mod recipes;
+mod refusal;
+mod risk;
+mod sessions;
+mod stockpile;
+mod sunrays;
+mod unsupported;
+mod validation;
+mod waitlist;
+
+pub use adaptive::{AdaptiveConfig, EnergyPolicy, PolicyStats, RewardWeights};
+pub use assist::{AssistConfig, AssistUsage, AssistedCraft};
+pub use auction::{AuctionConfig, AuctionResult};
+pub use billing::{Account, BillItem, BillingConfig};
+pub use cache::{CacheConfig, CacheStats};
+pub use conduct::{Ban, ConductConfig, ConductRecord, Offence};
+pub use config::EnterpriseConfig;
+pub use demand::{DemandForecast, DemandKind, DemandModel, DemandStats};
+pub use directory::{GalaxyDirectory, Referral};
+pub use graph::{RecipeEdge, RecipeGraph, RecipeNode};
+pub use handle::EnterpriseHandle;
+pub use ledger::{Ledger, Transaction, TransactionKind, TransactionOutcome};
+pub use planner::{CraftPlan, CraftPlanner, CraftStep, PlanError};
+pub use policy::ExplorerDemand;
+pub use postmortem::{DefenseGap, PlanetEvent, PostMortem, PostMortemConfig, TimedEvent};
+pub use priority::{ClassStats, PriorityClass};
+pub use quota::{QuotaConfig, QuotaUsage};
+pub use quote::{Quote, QuoteRequest};
+pub use recipes::{inputs_match, recipe_inputs, Recipes};
+pub use refusal::RefusalReason;
+pub use risk::{RiskAssessment, RiskConfig, RiskFactors, RiskLevel, RiskWeights};
+pub use sessions::{ExplorerSession, ExplorerSessions};
+pub use sunrays::{SunrayModelConfig, SunrayPattern};
+pub use unsupported::{UnsupportedDemandReport, UnsupportedRequests};
+pub use validation::CombineRejection;
+pub use waitlist::{DeferredDelivery, DeferredOutcome, WaitlistConfig};
+
 use common_game::components::planet::{
     DummyPlanetState, Planet, PlanetAI, PlanetState, PlanetType,
 };
@@ -12,6 +67,16 @@ use common_game::protocols::orchestrator_planet::*;
 use common_game::protocols::planet_explorer::*;
 use common_game::utils::ID;
 use crossbeam_channel::{Receiver, Sender};
+use assist::{combination_request, AssistOrder};
+use cache::CraftCache;
+use conduct::Ban as ConductBan;
+use handle::{Shared, SharedState};
+use postmortem::{Journal, PlanetEvent as JournalEvent};
+use stockpile::CarbonStockpile;
+use validation::validate_combination;
+use waitlist::{DeferredRequest, Waitlist};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 
 // The Enterprise planet AI
@@ -19,6 +84,13 @@ pub struct EnterpriseAi {
     running: bool,     // This parameter represents the current state of the AI
     num_explorers: u8, // This parameter represents how many explorers are on the planet
     planet_id: u32,    // This parameter represents the planet ID (used for logging purposes)
+    config: EnterpriseConfig,  // Tunable parameters of the AI
+    sessions: ExplorerSessions, // Sessions of the explorers on the planet, used to evict stale ones
+    shared: Shared,             // State visible to the host through an EnterpriseHandle
+    stockpile: CarbonStockpile, // Carbon made with idle energy, handed out before using a live cell
+    cache: CraftCache,          // Complex resources pre-crafted with idle energy
+    waitlist: Waitlist,         // Generation requests waiting for the next charged cell
+    journal: Journal,           // Recent events, reported to the host if the planet is destroyed
 }
 
 const ORCHESTRATOR: u32 = 0;
@@ -27,8 +99,8 @@ impl PlanetAI for EnterpriseAi {
     fn handle_sunray(
         &mut self,
         state: &mut PlanetState,
-        _generator: &Generator,
-        _combinator: &Combinator,
+        generator: &Generator,
+        combinator: &Combinator,
         sunray: Sunray,
     ) {
         // If there is already a charged cell, the planet will always try to build a rocket
@@ -37,7 +109,23 @@ impl PlanetAI for EnterpriseAi {
         // Then, we have other two possibilities: no explorers and explorers
         // If there are no explorers, the planet will prioritize self-defense
         // It will only try to build a rocket if it doesn't have any rocket
-        // If there are explorers, it will store the energy cell for the explorers
+        // If there are explorers, it will store the energy cell for the explorers,
+        // unless none of them asked for energy within the configured demand window
+        // With a cache or a stockpile configured, a charged cell that would make the sunray go
+        // wasted is used to pre-craft a complex resource, or turned into Carbon, when nobody
+        // is on the planet and a rocket is ready
+
+        // Explorers that crashed must not keep the planet out of defense mode
+        let now = Instant::now();
+        self.shared().sunrays.record(now);
+        self.evict_stale_explorers(now);
+        self.settle_auction(now);
+        let policy = self.shared().learner.policy();
+        let demand = policy.apply(self.night_guard(self.explorer_demand(now), state), state.has_rocket());
+        let risk = self.assess_risk(state, now);
+        let demand = self.risk_guard(demand, &risk, state);
+        let forecast = self.predicted_demand(now);
+        let pattern = self.shared().sunrays.pattern();
 
         let had_charged_cell = self.has_charged_cells(state);
 
@@ -51,7 +139,38 @@ impl PlanetAI for EnterpriseAi {
                 "had_charged_cells".to_string(),
                 had_charged_cell.to_string(),
             ),
+            ("energy_policy".to_string(), demand.as_str().to_string()),
+            (
+                "predicted_demand".to_string(),
+                format!("{:.2}", forecast.probability),
+            ),
+            (
+                "save_energy_for_explorers".to_string(),
+                demand.saves_energy().to_string(),
+            ),
         ]);
+        payload.insert("risk_score".to_string(), format!("{:.2}", risk.score));
+        payload.insert("risk_level".to_string(), risk.level.as_str().to_string());
+        if self.shared().learner.is_enabled() {
+            payload.insert("adaptive_policy".to_string(), policy.as_str().to_string());
+        }
+        if let Some(pattern) = &pattern {
+            payload.insert(
+                "sunray_rate_per_minute".to_string(),
+                format!("{:.2}", pattern.rate_per_minute),
+            );
+            payload.insert(
+                "sunray_burstiness".to_string(),
+                format!("{:.2}", pattern.burstiness),
+            );
+            if let Some(period) = pattern.period {
+                payload.insert("sunray_period".to_string(), period.to_string());
+            }
+            payload.insert(
+                "sunray_model_confidence".to_string(),
+                format!("{:.2}", pattern.confidence),
+            );
+        }
 
         LogEvent::new(
             Some(Participant {
@@ -70,57 +189,65 @@ impl PlanetAI for EnterpriseAi {
 
         // Here the planet tries to build a rocket with a charged cell
         let mut rocket_built = false;
-        if had_charged_cell && !state.has_rocket() {
-            if let Some((_, at)) = state.full_cell() {
-                match state.build_rocket(at) {
-                    Ok(_) => {
-                        rocket_built = true;
-                        payload.insert(
-                            "has_built_rocket_with_existing_charged_cells".to_string(),
-                            true.to_string(),
-                        );
-
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            Payload::from([("action".to_string(), "built_rocket".to_string())]),
-                        )
-                        .emit();
-                    }
-                    Err(e) => {
-                        payload.insert("build_rocket_error".to_string(), e);
+        if had_charged_cell && !state.has_rocket() && let Some((_, at)) = state.full_cell() {
+            match state.build_rocket(at) {
+                Ok(_) => {
+                    rocket_built = true;
+                    payload.insert(
+                        "has_built_rocket_with_existing_charged_cells".to_string(),
+                        true.to_string(),
+                    );
 
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Warning,
-                            Payload::from([(
-                                "action".to_string(),
-                                "rocket_build_failed".to_string(),
-                            )]),
-                        )
-                        .emit();
-                    }
+                    LogEvent::new(
+                        Some(Participant {
+                            actor_type: ActorType::Planet,
+                            id: self.planet_id,
+                        }),
+                        Some(Participant {
+                            actor_type: ActorType::SelfActor,
+                            id: self.planet_id,
+                        }),
+                        EventType::InternalPlanetAction,
+                        Channel::Debug,
+                        Payload::from([("action".to_string(), "built_rocket".to_string())]),
+                    )
+                    .emit();
+                }
+                Err(e) => {
+                    payload.insert("build_rocket_error".to_string(), e);
+
+                    LogEvent::new(
+                        Some(Participant {
+                            actor_type: ActorType::Planet,
+                            id: self.planet_id,
+                        }),
+                        Some(Participant {
+                            actor_type: ActorType::SelfActor,
+                            id: self.planet_id,
+                        }),
+                        EventType::InternalPlanetAction,
+                        Channel::Warning,
+                        Payload::from([(
+                            "action".to_string(),
+                            "rocket_build_failed".to_string(),
+                        )]),
+                    )
+                    .emit();
                 }
             }
         }
 
-        match state.charge_cell(sunray) {
+        if demand == ExplorerDemand::NoExplorers && state.has_rocket() {
+            // Pre-crafting takes precedence, the stockpile gets the energy left
+            let precrafted = self.precraft(state, combinator, now);
+            payload.insert("precrafted".to_string(), precrafted.to_string());
+            let stockpiled = !precrafted && self.stockpile_carbon(state, generator);
+            payload.insert("stockpiled_carbon".to_string(), stockpiled.to_string());
+        }
+
+        let charged = state.charge_cell(sunray);
+        let wasted = charged.is_some();
+        match charged {
             Some(_) => {
                 payload.insert("received_sunray".to_string(), "wasted".to_string());
 
@@ -157,40 +284,38 @@ impl PlanetAI for EnterpriseAi {
                 )
                 .emit();
 
-                if self.num_explorers == 0 && !state.has_rocket() {
-                    if let Some((_, at)) = state.full_cell() {
-                        match state.build_rocket(at) {
-                            Ok(_) => {
-                                rocket_built = true;
-                                payload.insert(
-                                    "has_built_rocket_with_new_energy_cell".to_string(),
-                                    true.to_string(),
-                                );
-
-                                LogEvent::new(
-                                    Some(Participant {
-                                        actor_type: ActorType::Planet,
-                                        id: self.planet_id,
-                                    }),
-                                    Some(Participant {
-                                        actor_type: ActorType::SelfActor,
-                                        id: self.planet_id,
-                                    }),
-                                    EventType::InternalPlanetAction,
-                                    Channel::Debug,
-                                    Payload::from([(
-                                        "action".to_string(),
-                                        "has_built_rocket_with_new_energy_cell".to_string(),
-                                    )]),
-                                )
-                                .emit();
-                            }
-                            Err(_e) => { //Should we add the error string in the log?
-                                payload.insert(
+                if !demand.saves_energy() && !state.has_rocket() && let Some((_, at)) = state.full_cell() {
+                    match state.build_rocket(at) {
+                        Ok(_) => {
+                            rocket_built = true;
+                            payload.insert(
+                                "has_built_rocket_with_new_energy_cell".to_string(),
+                                true.to_string(),
+                            );
+
+                            LogEvent::new(
+                                Some(Participant {
+                                    actor_type: ActorType::Planet,
+                                    id: self.planet_id,
+                                }),
+                                Some(Participant {
+                                    actor_type: ActorType::SelfActor,
+                                    id: self.planet_id,
+                                }),
+                                EventType::InternalPlanetAction,
+                                Channel::Debug,
+                                Payload::from([(
+                                    "action".to_string(),
                                     "has_built_rocket_with_new_energy_cell".to_string(),
-                                    false.to_string(),
-                                );
-                            }
+                                )]),
+                            )
+                            .emit();
+                        }
+                        Err(_e) => { //Should we add the error string in the log?
+                            payload.insert(
+                                "has_built_rocket_with_new_energy_cell".to_string(),
+                                false.to_string(),
+                            );
                         }
                     }
                 }
@@ -221,6 +346,18 @@ impl PlanetAI for EnterpriseAi {
             payload,
         )
         .emit();
+        self.score_policy(wasted);
+        self.journal.record(
+            JournalEvent::Sunray {
+                decision: demand,
+                used: !wasted,
+                rocket_built,
+            },
+            now,
+        );
+        self.fulfil_deferred(generator, state, now);
+        self.process_assisted_crafts(combinator, state, now);
+        self.sync_state(state);
     }
 
     fn handle_internal_state_req(
@@ -229,7 +366,10 @@ impl PlanetAI for EnterpriseAi {
         _generator: &Generator,
         _combinator: &Combinator,
     ) -> DummyPlanetState {
-        let payload = Payload::from([("request".to_string(), "internal_state".to_string())]);
+        let mut payload = Payload::from([("request".to_string(), "internal_state".to_string())]);
+        if let Some(eta) = self.energy_eta(state) {
+            payload.insert("energy_eta_ms".to_string(), eta.as_millis().to_string());
+        }
 
         LogEvent::new(
             Some(Participant {
@@ -278,6 +418,10 @@ impl PlanetAI for EnterpriseAi {
         )
         .emit();
 
+        let now = Instant::now();
+        self.shared().risk.record_asteroid(now);
+        self.evict_stale_explorers(now);
+
         if !self.is_running() {
             let payload = Payload::from([("error".to_string(), "ai_not_running".to_string())]);
             LogEvent::new(
@@ -294,6 +438,7 @@ impl PlanetAI for EnterpriseAi {
                 payload,
             )
             .emit();
+            self.report_destruction(now);
             return None;
         }
 
@@ -318,6 +463,9 @@ impl PlanetAI for EnterpriseAi {
                     payload,
                 )
                 .emit();
+                self.shared().learner.record_asteroid(true);
+                self.journal.record(JournalEvent::Asteroid { defended: true }, now);
+                self.journal.rocket_launched();
                 Some(rocket)
             }
             None => {
@@ -343,7 +491,16 @@ impl PlanetAI for EnterpriseAi {
                     )
                     .emit();
                 }
-                state.take_rocket()
+                let rocket = state.take_rocket();
+                self.shared().learner.record_asteroid(rocket.is_some());
+                self.journal.record(JournalEvent::Asteroid { defended: rocket.is_some() }, now);
+                if rocket.is_some() {
+                    self.journal.rocket_launched();
+                } else {
+                    self.report_destruction(now);
+                }
+                self.sync_state(state);
+                rocket
             }
         }
     }
@@ -383,6 +540,11 @@ impl PlanetAI for EnterpriseAi {
         )
         .emit();
 
+        let now = Instant::now();
+        self.evict_stale_explorers(now);
+        self.settle_auction(now);
+        self.expire_deferred(now);
+
         if !self.is_running() {
             let payload = Payload::from([
                 ("error".to_string(), "ai_not_running".to_string()),
@@ -405,6 +567,84 @@ impl PlanetAI for EnterpriseAi {
             return Some(PlanetToExplorer::Stopped);
         }
 
+        // Crafts ordered by the host for any explorer are served while the planet is awake
+        self.process_assisted_crafts(combinator, state, now);
+
+        // Blacklisted explorers are refused before their activity is recorded, so they can still
+        // be evicted. The others are scored on their behaviour.
+        if self.shared().conduct.is_banned(explorer_id, now) {
+            return self.refuse(explorer_id, msg_type, msg, RefusalReason::Blacklisted);
+        }
+        let registered = self.record_explorer_activity(explorer_id, now);
+        let unsupported = unsupported_resource(&msg, generator, combinator);
+        let mut offences = Vec::new();
+        if !registered {
+            offences.push(Offence::Unregistered);
+        }
+        if self.shared().conduct.record_message(explorer_id, now) {
+            offences.push(Offence::Spam);
+        }
+        if unsupported.is_some() {
+            offences.push(Offence::InvalidRequest);
+        }
+        let mut banned = false;
+        for offence in offences {
+            banned |= self.record_offence(explorer_id, offence, now);
+        }
+        if banned {
+            return self.refuse(explorer_id, msg_type, msg, RefusalReason::Blacklisted);
+        }
+
+        // Requests the planet can't serve use no cell, the explorer is pointed to another planet
+        // whatever its quotas and its bill
+        if let Some(resource) = unsupported {
+            self.shared().unsupported.record(resource, explorer_id, now);
+            self.refer_explorer(explorer_id, resource, now);
+        }
+
+        // Explorers over their quotas, or bidding for the cell, get a refusal instead of an answer
+        let demand_kind = match &msg {
+            ExplorerToPlanet::GenerateResourceRequest { .. } => Some(DemandKind::Generate),
+            ExplorerToPlanet::CombineResourceRequest { .. } => Some(DemandKind::Combine),
+            ExplorerToPlanet::AvailableEnergyCellRequest { .. } => Some(DemandKind::EnergyQuery),
+            _ => None,
+        };
+        let requested = requested_resource(&msg, generator, combinator);
+        let needs_cell = requested.is_some();
+        if needs_cell {
+            let risk = self.assess_risk(state, now);
+            self.log_risk(explorer_id, &risk);
+        }
+        if needs_cell && self.higher_priority_waiting(explorer_id, now) {
+            return self.refuse(explorer_id, msg_type, msg, RefusalReason::KeptForHigherPriority);
+        }
+        let quota_check = {
+            let mut shared = self.shared();
+            shared
+                .quotas
+                .check_message(explorer_id, now)
+                .and_then(|_| match requested {
+                    Some(resource) => shared
+                        .auction
+                        .check_cell(explorer_id, now)
+                        .and_then(|_| shared.quotas.check_cell(explorer_id, now))
+                        .and_then(|_| shared.billing.check(explorer_id, resource)),
+                    None => Ok(()),
+                })
+        };
+        if let Err(reason) = quota_check {
+            return self.refuse(explorer_id, msg_type, msg, reason);
+        }
+
+        // Only the requests the planet processes count as demand, an explorer wants energy only if
+        // the planet can serve it
+        if let Some(kind) = demand_kind {
+            if needs_cell {
+                self.sessions.record_demand(explorer_id, now);
+            }
+            self.shared().demand.record(explorer_id, kind, now);
+        }
+
         match msg {
             ExplorerToPlanet::AvailableEnergyCellRequest { .. } => {
                 // Counts how many energy cells are currently charged (1 or 0 in C-type planet case)
@@ -413,10 +653,13 @@ impl PlanetAI for EnterpriseAi {
                     .filter(|energy_cell| energy_cell.is_charged())
                     .count() as u32; 
 
-                let payload = Payload::from([
+                let mut payload = Payload::from([
                     ("request".to_string(), "available_energy_cells".to_string()),
                     ("count".to_string(), available.to_string()),
                 ]);
+                if let Some(eta) = self.energy_eta(state) {
+                    payload.insert("energy_eta_ms".to_string(), eta.as_millis().to_string());
+                }
 
                 LogEvent::new(
                     Some(Participant {
@@ -438,16 +681,40 @@ impl PlanetAI for EnterpriseAi {
                 })
             }
             ExplorerToPlanet::CombineResourceRequest { explorer_id, msg } => {
-                Some(PlanetToExplorer::CombineResourceResponse {
-                    complex_response: self.handle_combine_request(explorer_id, msg, combinator, state),
-                })
+                let resource = requested_type(&msg);
+                self.cache.record_request(resource);
+                // A cache hit uses no cell, only the bill and the ledger record it
+                let (complex_response, cell_used) = match self.cache.take(resource) {
+                    Some(complex) => (Ok(self.serve_from_cache(explorer_id, complex, msg, now)), false),
+                    None => (self.handle_combine_request(explorer_id, msg, combinator, state, now), true),
+                };
+                if complex_response.is_ok() {
+                    if cell_used {
+                        self.record_cell_use(explorer_id, now);
+                    }
+                    self.sync_state(state);
+                }
+                Some(PlanetToExplorer::CombineResourceResponse { complex_response })
             }
             ExplorerToPlanet::GenerateResourceRequest {
                 explorer_id,
-                resource,
-            } => Some(PlanetToExplorer::GenerateResourceResponse {
-                resource: self.handle_resource_request(explorer_id, resource, generator, state),
-            }),
+                resource: requested,
+            } => {
+                let served = self.handle_resource_request(explorer_id, requested, generator, state, now);
+                // Stockpiled carbon uses no cell, only the ledger records it
+                if let Some((_, cell_used)) = served {
+                    if cell_used {
+                        self.record_cell_use(explorer_id, now);
+                    }
+                    self.sync_state(state);
+                } else if generator.contains(requested) && state.full_cell().is_none() {
+                    // The explorer still gets its answer, the resource follows through the host
+                    self.defer_request(explorer_id, requested, now);
+                }
+                Some(PlanetToExplorer::GenerateResourceResponse {
+                    resource: served.map(|(resource, _)| resource),
+                })
+            }
             ExplorerToPlanet::SupportedCombinationRequest { .. } => {
                 // C-type planets support unbounded combination rules (up to 6)
                 let payload = Payload::from([
@@ -517,7 +784,11 @@ impl PlanetAI for EnterpriseAi {
         _combinator: &Combinator,
         explorer_id: ID,
     ) {
-        self.num_explorers += 1; // The number of explorers inside the planet is increased, an explorer is coming
+        let now = Instant::now();
+        self.evict_stale_explorers(now);
+        self.sessions.arrive(explorer_id, now);
+        self.num_explorers = self.explorer_count(); // The number of explorers inside the planet is increased, an explorer is coming
+        self.journal.record(JournalEvent::ExplorerArrived { explorer_id }, now);
 
         let payload = Payload::from([("action".to_string(), "explorer_arrival".to_string())]);
         LogEvent::new(
@@ -566,8 +837,33 @@ impl PlanetAI for EnterpriseAi {
         _combinator: &Combinator,
         explorer_id: ID,
     ) {
-        if self.num_explorers > 0 {
-            self.num_explorers -= 1; // The number of explorers inside the planet is decreased, the explorer is leaving
+        self.shared().auction.withdraw(explorer_id);
+        self.cancel_deferred(explorer_id);
+        let was_evicted = self.sessions.was_evicted(explorer_id);
+        if self.sessions.depart(explorer_id).is_some() {
+            self.num_explorers = self.explorer_count(); // The number of explorers inside the planet is decreased, the explorer is leaving
+            self.journal.record(JournalEvent::ExplorerLeft { explorer_id }, Instant::now());
+        } else if was_evicted {
+            // The explorer was already removed for inactivity, its departure is just confirmed
+            let payload = Payload::from([
+                ("cause".to_string(), "explorer_already_evicted".to_string()),
+                ("explorer_id".to_string(), explorer_id.to_string()),
+            ]);
+
+            LogEvent::new(
+                Some(Participant {
+                    actor_type: ActorType::Planet,
+                    id: self.planet_id,
+                }),
+                Some(Participant {
+                    actor_type: ActorType::SelfActor,
+                    id: self.planet_id,
+                }),
+                EventType::InternalExplorerAction,
+                Channel::Debug,
+                payload,
+            )
+            .emit();
         } else {
             let payload =
                 Payload::from([("cause".to_string(), "no_explorer_arrived_yet".to_string())]);
@@ -629,8 +925,11 @@ impl PlanetAI for EnterpriseAi {
         .emit();
     }
 
-    fn on_start(&mut self, _state: &PlanetState, _generator: &Generator, _combinator: &Combinator) {
+    fn on_start(&mut self, state: &PlanetState, generator: &Generator, combinator: &Combinator) {
         self.running = true; // Flags the parameter to true, the planet is active
+        self.shared().recipes = Recipes::of(generator, combinator);
+        self.sync_state(state);
+        self.sessions.suspend_all();
         self.num_explorers = 0; // There are no explorers when the planet is created
 
         let payload = Payload::from([
@@ -656,6 +955,7 @@ impl PlanetAI for EnterpriseAi {
 
     fn on_stop(&mut self, _state: &PlanetState, _generator: &Generator, _combinator: &Combinator) {
         self.running = false; // Flags the parameter to false, the planet is stopped
+        self.sessions.suspend_all();
         self.num_explorers = 0; // The number of explorers is brought to zero
 
         let payload = Payload::from([("action".to_string(), "stopped".to_string())]);
@@ -679,6 +979,10 @@ impl PlanetAI for EnterpriseAi {
 
 impl EnterpriseAi {
     pub fn new(planet_id: u32) -> Self {
+        Self::with_config(planet_id, EnterpriseConfig::default())
+    }
+
+    pub fn with_config(planet_id: u32, config: EnterpriseConfig) -> Self {
         let payload = Payload::from([
             ("action".to_string(), "init".to_string()),
             ("planet_type".to_string(), "C".to_string()),
@@ -703,8 +1007,20 @@ impl EnterpriseAi {
             running: false,
             num_explorers: 0,
             planet_id,
+            shared: Arc::new(Mutex::new(SharedState::new(&config))),
+            cache: CraftCache::new(config.cache.clone()),
+            waitlist: Waitlist::new(config.waitlist.clone()),
+            journal: Journal::new(config.post_mortem.clone()),
+            config,
+            sessions: ExplorerSessions::new(),
+            stockpile: CarbonStockpile::new(),
         }
     }
+
+    /// Returns a handle the host can use to inspect the AI once it is moved into the planet
+    pub fn handle(&self) -> EnterpriseHandle {
+        EnterpriseHandle::new(Arc::clone(&self.shared))
+    }
     pub fn is_running(&self) -> bool {
         let payload = Payload::from([("is_running".to_string(), self.running.to_string())]);
 
@@ -726,22 +1042,26 @@ impl EnterpriseAi {
         self.running
     }
 
-    fn has_charged_cells(&self, state: &mut PlanetState) -> bool {
-        //Enterprise (planet of type C) support only 1 energy cell
-        state.full_cell().is_some()
+    fn explorer_count(&self) -> u8 {
+        u8::try_from(self.sessions.len()).unwrap_or(u8::MAX)
     }
 
-    fn handle_resource_request(
-        &mut self,
-        _explorer_id: u32,
-        request: BasicResourceType,
-        generator: &Generator,
-        state: &mut PlanetState,
-    ) -> Option<BasicResource> {
-        if !generator.contains(request) {
+    // Removes the explorers that have been silent for longer than the configured timeout
+    fn evict_stale_explorers(&mut self, now: Instant) {
+        let Some(timeout) = self.config.explorer_timeout else {
+            return;
+        };
+
+        for explorer_id in self.sessions.evict_expired(now, timeout) {
+            self.num_explorers = self.explorer_count();
+            self.cancel_deferred(explorer_id);
+            self.journal.record(JournalEvent::ExplorerLeft { explorer_id }, now);
+
             let payload = Payload::from([
-                ("error".to_string(), "unsupported_resource".to_string()),
-                ("requested_resource".to_string(), format!("{:?}", request)),
+                ("action".to_string(), "explorer_evicted".to_string()),
+                ("explorer_id".to_string(), explorer_id.to_string()),
+                ("timeout_ms".to_string(), timeout.as_millis().to_string()),
+                ("explorer_count".to_string(), self.num_explorers.to_string()),
             ]);
             LogEvent::new(
                 Some(Participant {
@@ -752,636 +1072,1478 @@ impl EnterpriseAi {
                     actor_type: ActorType::SelfActor,
                     id: self.planet_id,
                 }),
-                EventType::InternalPlanetAction,
+                EventType::InternalExplorerAction,
                 Channel::Warning,
                 payload,
             )
             .emit();
-            return None;
-        } else {
-            let energy_cell = match state.full_cell() {
-                Some((c, i)) => {
-                    let payload = Payload::from([
-                        ("action".to_string(), "using_charged_cell".to_string()),
-                        ("cell_index".to_string(), i.to_string()),
-                    ]);
-                    LogEvent::new(
-                        Some(Participant {
-                            actor_type: ActorType::Planet,
-                            id: self.planet_id,
-                        }),
-                        Some(Participant {
-                            actor_type: ActorType::SelfActor,
-                            id: self.planet_id,
-                        }),
-                        EventType::InternalPlanetAction,
-                        Channel::Trace,
-                        payload,
-                    )
-                    .emit();
-                    c
-                }
-                None => {
-                    let payload =
-                        Payload::from([("error".to_string(), "no_charged_cell".to_string())]);
-                    LogEvent::new(
-                        Some(Participant {
-                            actor_type: ActorType::Planet,
-                            id: self.planet_id,
-                        }),
-                        Some(Participant {
-                            actor_type: ActorType::SelfActor,
-                            id: self.planet_id,
-                        }),
-                        EventType::InternalPlanetAction,
-                        Channel::Debug,
-                        payload,
-                    )
-                    .emit();
-                    return None;
-                }
-            };
+        }
+    }
 
-            let new_resource = generator
-                .make_carbon(energy_cell)
-                .map(|new_carbon| BasicResource::Carbon(new_carbon));
+    // Refreshes the session of an explorer that sent a message
+    // Returns false if the explorer never arrived on the planet
+    fn record_explorer_activity(&mut self, explorer_id: ID, now: Instant) -> bool {
+        let was_evicted = self.sessions.was_evicted(explorer_id);
+        let registered = self.sessions.touch(explorer_id, now);
+        if registered && was_evicted {
+            self.num_explorers = self.explorer_count();
 
-            match new_resource {
-                Ok(new_resource) => {
-                    let payload = Payload::from([
-                        ("action".to_string(), "resource_generated".to_string()),
-                        ("resource".to_string(), "Carbon".to_string()),
-                    ]);
-                    LogEvent::new(
-                        Some(Participant {
-                            actor_type: ActorType::Planet,
-                            id: self.planet_id,
-                        }),
-                        Some(Participant {
-                            actor_type: ActorType::SelfActor,
-                            id: self.planet_id,
-                        }),
-                        EventType::InternalPlanetAction,
-                        Channel::Debug,
-                        payload,
-                    )
-                    .emit();
-                    return Some(new_resource);
-                }
-                Err(e) => {
-                    let payload = Payload::from([
-                        ("error".to_string(), "generation_failed".to_string()),
-                        ("error_message".to_string(), e),
-                    ]);
-                    LogEvent::new(
-                        Some(Participant {
-                            actor_type: ActorType::Planet,
-                            id: self.planet_id,
-                        }),
-                        Some(Participant {
-                            actor_type: ActorType::SelfActor,
-                            id: self.planet_id,
-                        }),
-                        EventType::InternalPlanetAction,
-                        Channel::Debug,
-                        payload,
-                    )
-                    .emit();
-                }
-            };
+            let payload = Payload::from([
+                ("action".to_string(), "explorer_session_revived".to_string()),
+                ("explorer_id".to_string(), explorer_id.to_string()),
+                ("explorer_count".to_string(), self.num_explorers.to_string()),
+            ]);
+            LogEvent::new(
+                Some(Participant {
+                    actor_type: ActorType::Planet,
+                    id: self.planet_id,
+                }),
+                Some(Participant {
+                    actor_type: ActorType::SelfActor,
+                    id: self.planet_id,
+                }),
+                EventType::InternalExplorerAction,
+                Channel::Debug,
+                payload,
+            )
+            .emit();
         }
-        None
+        registered
     }
 
-    fn handle_combine_request(
-        &mut self,
-        _explorer_id: u32, //Should we use the explorer_id in the log?
-        request: ComplexResourceRequest,
-        combinator: &Combinator,
-        state: &mut PlanetState,
-    ) -> Result<ComplexResource, (String, GenericResource, GenericResource)> {
+    // Adds an offence to the explorer score, returning true if it got the explorer blacklisted
+    fn record_offence(&mut self, explorer_id: ID, offence: Offence, now: Instant) -> bool {
+        let ban = self.shared().conduct.record_offence(explorer_id, offence, now);
 
-        match request {
-            // The "AIPartner" complex resource takes Robot + Diamond
-            ComplexResourceRequest::AIPartner(r1, r2) => {
-                let energy_cell = match state.full_cell() {
-                    Some((c, _)) => c,
-                    None => {
-                        let error_payload = Payload::from([
-                            ("error".to_string(), "no_energy_cell_available".to_string()),
-                            ("request_type".to_string(), "AIPartner".to_string()),
-                        ]);
+        let payload = Payload::from([
+            ("action".to_string(), "explorer_offence".to_string()),
+            ("offence".to_string(), offence.as_str().to_string()),
+            ("explorer_id".to_string(), explorer_id.to_string()),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::SelfActor,
+                id: self.planet_id,
+            }),
+            EventType::InternalExplorerAction,
+            Channel::Debug,
+            payload,
+        )
+        .emit();
 
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            error_payload,
-                        )
-                        .emit();
+        let Some(ban) = ban else {
+            return false;
+        };
+        let mut payload = Payload::from([
+            ("action".to_string(), "explorer_blacklisted".to_string()),
+            ("explorer_id".to_string(), explorer_id.to_string()),
+        ]);
+        match ban {
+            ConductBan::Temporary { until } => {
+                payload.insert("ban".to_string(), "temporary".to_string());
+                payload.insert(
+                    "ban_ms".to_string(),
+                    until.saturating_duration_since(now).as_millis().to_string(),
+                );
+            }
+            ConductBan::Permanent => {
+                payload.insert("ban".to_string(), "permanent".to_string());
+            }
+        }
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::SelfActor,
+                id: self.planet_id,
+            }),
+            EventType::InternalExplorerAction,
+            Channel::Warning,
+            payload,
+        )
+        .emit();
+        true
+    }
 
-                        return Err((
-                            "No energy cell available".to_string(),
-                            GenericResource::ComplexResources(ComplexResource::Robot(r1)),
-                            GenericResource::ComplexResources(ComplexResource::Diamond(r2)),
-                        ));
-                    }
-                };
-                let complex = combinator.make_aipartner(r1, r2, energy_cell);
+    // Looks up in the galaxy directory where the explorer can find a resource Enterprise can't provide
+    fn refer_explorer(&mut self, explorer_id: ID, resource: ResourceType, now: Instant) {
+        let planet_id = {
+            let mut shared = self.shared();
+            let planet_id = shared.directory.best_next_hop(resource);
+            shared.referrals.record(Referral {
+                explorer_id,
+                resource,
+                planet_id,
+                at: now,
+            });
+            planet_id
+        };
 
-                match complex {
-                    Ok(complex) => {
-                        let success_payload = Payload::from([
-                            ("action".to_string(), "combine_success".to_string()),
-                            ("resource".to_string(), "AIPartner".to_string()),
-                        ]);
+        let payload = Payload::from([
+            ("action".to_string(), "explorer_referred".to_string()),
+            ("explorer_id".to_string(), explorer_id.to_string()),
+            ("resource".to_string(), format!("{:?}", resource)),
+            (
+                "referred_planet".to_string(),
+                planet_id.map_or("none".to_string(), |id| id.to_string()),
+            ),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::Explorer,
+                id: explorer_id,
+            }),
+            EventType::InternalExplorerAction,
+            Channel::Info,
+            payload,
+        )
+        .emit();
+    }
 
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            success_payload,
-                        )
-                        .emit();
+    // Runs the assisted crafts ordered by the host, as long as there is energy for them
+    fn process_assisted_crafts(&mut self, combinator: &Combinator, state: &mut PlanetState, now: Instant) {
+        // Orders wait for the next sunray when there is no charged cell
+        while state.full_cell().is_some() {
+            let Some(order) = self.shared().assists.pending.pop_front() else {
+                break;
+            };
+            let explorer_id = order.explorer_id;
+            let craft = self.assisted_craft(order, combinator, state, now);
 
-                        Ok(ComplexResource::AIPartner(complex))
-                    }
-                    Err((s, r1, r2)) => {
-                        let fail_payload = Payload::from([
-                            ("error".to_string(), "combine_failed".to_string()),
-                            ("request_type".to_string(), "AIPartner".to_string()),
-                            ("error_message".to_string(), s.clone()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            fail_payload,
-                        )
-                        .emit();
+            let mut payload = Payload::from([
+                ("action".to_string(), "assisted_craft".to_string()),
+                ("explorer_id".to_string(), explorer_id.to_string()),
+                ("resource".to_string(), format!("{:?}", craft.resource)),
+                ("carbon_supplied".to_string(), craft.carbon_supplied.to_string()),
+                ("success".to_string(), craft.result.is_ok().to_string()),
+            ]);
+            if let Err((message, _)) = &craft.result {
+                payload.insert("error_message".to_string(), message.clone());
+            }
+            LogEvent::new(
+                Some(Participant {
+                    actor_type: ActorType::Planet,
+                    id: self.planet_id,
+                }),
+                Some(Participant {
+                    actor_type: ActorType::Explorer,
+                    id: explorer_id,
+                }),
+                EventType::InternalExplorerAction,
+                Channel::Info,
+                payload,
+            )
+            .emit();
 
-                        Err((
-                            s,
-                            GenericResource::ComplexResources(ComplexResource::Robot(r1)),
-                            GenericResource::ComplexResources(ComplexResource::Diamond(r2)),
-                        ))
-                    }
+            self.shared().assists.complete(explorer_id, craft);
+        }
+        self.sync_state(state);
+    }
+
+    // Completes the explorer inputs with stockpiled Carbon and combines them
+    fn assisted_craft(
+        &mut self,
+        order: AssistOrder,
+        combinator: &Combinator,
+        state: &mut PlanetState,
+        now: Instant,
+    ) -> AssistedCraft {
+        let AssistOrder {
+            explorer_id,
+            resource,
+            mut provided,
+        } = order;
+        let reject = |message: String, provided: Vec<GenericResource>| AssistedCraft {
+            resource,
+            carbon_supplied: 0,
+            result: Err((message, provided)),
+        };
+
+        // The cell goes through the same checks as a deferred request
+        if let Err(reason) = self.check_hosted_request(explorer_id, ResourceType::Complex(resource), now) {
+            return reject(reason.message(), provided);
+        }
+
+        // Puts each input the explorer brought in its slot of the recipe
+        let (a, b) = recipe_inputs(resource);
+        let mut slots = [None, None];
+        for (slot, input) in slots.iter_mut().zip([a, b]) {
+            if let Some(at) = provided.iter().position(|r| r.get_type() == input) {
+                *slot = Some(provided.remove(at));
+            }
+        }
+
+        // The planet can only fill the Carbon slots, within the recipe and the explorer allowance
+        let carbon = ResourceType::Basic(BasicResourceType::Carbon);
+        let missing = [a, b]
+            .iter()
+            .zip(&slots)
+            .filter(|(_, slot)| slot.is_none())
+            .map(|(input, _)| *input)
+            .collect::<Vec<_>>();
+        let needed = missing.len() as u32;
+        let allowance = self.config.assist.recipes.get(&resource).copied().unwrap_or(0);
+        let supplied_so_far = self
+            .shared()
+            .assists
+            .usage(explorer_id)
+            .map_or(0, |usage| usage.carbon_supplied);
+        let within_cap = self
+            .config
+            .assist
+            .max_carbon_per_explorer
+            .is_none_or(|max| supplied_so_far + needed <= max);
+        if !provided.is_empty()
+            || missing.iter().any(|input| *input != carbon)
+            || needed > allowance
+            || !within_cap
+            || self.stockpile.len() < missing.len()
+        {
+            provided.extend(slots.into_iter().flatten());
+            return reject(
+                "Assisted crafting not available for these inputs".to_string(),
+                provided,
+            );
+        }
+
+        let supplied = slots.each_ref().map(Option::is_none);
+        for slot in slots.iter_mut().filter(|slot| slot.is_none()) {
+            *slot = self.stockpile.take().map(Carbon::to_generic);
+        }
+        let [Some(r1), Some(r2)] = slots else {
+            // The stockpile was checked above, nothing can be missing here
+            provided.extend(slots.into_iter().flatten());
+            return reject("Missing inputs".to_string(), provided);
+        };
+        let request = match combination_request(resource, r1, r2) {
+            Ok(request) => request,
+            Err((r1, r2)) => {
+                let returned = self.give_back_inputs([r1, r2], supplied);
+                return reject("Invalid inputs".to_string(), returned);
+            }
+        };
+
+        match self.handle_combine_request(explorer_id, request, combinator, state, now) {
+            Ok(complex) => {
+                self.record_cell_use(explorer_id, now);
+                AssistedCraft {
+                    resource,
+                    carbon_supplied: needed,
+                    result: Ok(complex),
                 }
             }
-            // The "Diamond" complex resource takes Carbon + Carbon
-            ComplexResourceRequest::Diamond(r1, r2) => {
-                let energy_cell = match state.full_cell() {
-                    Some((c, _)) => c,
-                    None => {
-                        let error_payload = Payload::from([
-                            ("error".to_string(), "no_energy_cell_available".to_string()),
-                            ("request_type".to_string(), "Diamond".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            error_payload,
-                        )
-                        .emit();
+            Err((message, r1, r2)) => {
+                let returned = self.give_back_inputs([r1, r2], supplied);
+                reject(message, returned)
+            }
+        }
+    }
 
-                        return Err((
-                            "No energy cell available".to_string(),
-                            GenericResource::BasicResources(BasicResource::Carbon(r1)),
-                            GenericResource::BasicResources(BasicResource::Carbon(r2)),
-                        ));
-                    }
-                };
+    // The inputs supplied by the planet go back where they came from, the rest is returned for the explorer
+    fn give_back_inputs(&mut self, inputs: [GenericResource; 2], supplied: [bool; 2]) -> Vec<GenericResource> {
+        let mut returned = Vec::new();
+        for (input, supplied) in inputs.into_iter().zip(supplied) {
+            if supplied {
+                self.return_input(input);
+            } else {
+                returned.push(input);
+            }
+        }
+        returned
+    }
 
-                let complex = combinator.make_diamond(r1, r2, energy_cell);
+    // Accounts a charged cell consumed on behalf of an explorer
+    fn record_cell_use(&mut self, explorer_id: ID, now: Instant) {
+        let delivered = {
+            let mut shared = self.shared();
+            shared.quotas.record_cell(explorer_id, now);
+            shared.priorities.record_served(explorer_id);
+            shared.learner.record_cell_served();
+            shared.auction.delivered(explorer_id)
+        };
+        if delivered {
+            let payload = Payload::from([
+                ("action".to_string(), "auction_cell_delivered".to_string()),
+                ("explorer_id".to_string(), explorer_id.to_string()),
+            ]);
+            LogEvent::new(
+                Some(Participant {
+                    actor_type: ActorType::Planet,
+                    id: self.planet_id,
+                }),
+                Some(Participant {
+                    actor_type: ActorType::Explorer,
+                    id: explorer_id,
+                }),
+                EventType::InternalExplorerAction,
+                Channel::Debug,
+                payload,
+            )
+            .emit();
+        }
+    }
 
-                match complex {
-                    Ok(complex) => {
-                        let success_payload = Payload::from([
-                            ("action".to_string(), "combine_success".to_string()),
-                            ("resource".to_string(), "Diamond".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            success_payload,
-                        )
-                        .emit();
+    // Awards the next charged cell once the bidding window is over, keeping the winner payment.
+    // What the stockpile and the cache have no room for is refunded to the winner.
+    fn settle_auction(&mut self, now: Instant) {
+        let Some((result, payment)) = self.shared().auction.settle(now) else {
+            return;
+        };
+        let refused = payment
+            .into_iter()
+            .filter_map(|resource| self.keep_payment(resource).err())
+            .collect::<Vec<_>>();
+        let refunded = refused.len();
+        self.shared().auction.refund(result.winner, refused);
 
-                        Ok(ComplexResource::Diamond(complex))
-                    }
-                    Err((s, r1, r2)) => {
-                        let fail_payload = Payload::from([
-                            ("error".to_string(), "combine_failed".to_string()),
-                            ("request_type".to_string(), "Diamond".to_string()),
-                            ("error_message".to_string(), s.clone()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            fail_payload,
-                        )
-                        .emit();
+        let payload = Payload::from([
+            ("action".to_string(), "auction_settled".to_string()),
+            ("winner".to_string(), result.winner.to_string()),
+            ("priority".to_string(), result.priority.to_string()),
+            ("offered".to_string(), result.offered.to_string()),
+            ("refunded".to_string(), refunded.to_string()),
+            ("bids".to_string(), result.bids.to_string()),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::Explorer,
+                id: result.winner,
+            }),
+            EventType::InternalExplorerAction,
+            Channel::Info,
+            payload,
+        )
+        .emit();
+    }
+
+    // Queues a generation request made while no cell is charged, if the waitlist is enabled
+    fn defer_request(&mut self, explorer_id: ID, resource: BasicResourceType, now: Instant) {
+        if !self.waitlist.defer(explorer_id, resource, now) {
+            return;
+        }
+
+        let payload = Payload::from([
+            ("action".to_string(), "request_deferred".to_string()),
+            ("explorer_id".to_string(), explorer_id.to_string()),
+            ("resource".to_string(), format!("{:?}", resource)),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::Explorer,
+                id: explorer_id,
+            }),
+            EventType::InternalExplorerAction,
+            Channel::Debug,
+            payload,
+        )
+        .emit();
+    }
 
-                        Err((
-                            s,
-                            GenericResource::BasicResources(BasicResource::Carbon(r1)),
-                            GenericResource::BasicResources(BasicResource::Carbon(r2)),
-                        ))
+    // Serves the requests waiting for a charged cell, first come first served
+    fn fulfil_deferred(&mut self, generator: &Generator, state: &mut PlanetState, now: Instant) {
+        self.expire_deferred(now);
+        while state.full_cell().is_some() {
+            let Some(request) = self.waitlist.next() else {
+                break;
+            };
+            let explorer_id = request.explorer_id;
+
+            // The cell goes through the same checks as a live request
+            let check = self.check_hosted_request(explorer_id, ResourceType::Basic(request.resource), now);
+            let outcome = match check {
+                Err(reason) => DeferredOutcome::Refused(reason),
+                Ok(()) => {
+                    match self.handle_resource_request(explorer_id, request.resource, generator, state, now) {
+                        Some((resource, cell_used)) => {
+                            if cell_used {
+                                self.record_cell_use(explorer_id, now);
+                            }
+                            DeferredOutcome::Delivered(resource)
+                        }
+                        None => {
+                            self.waitlist.requeue(request);
+                            break;
+                        }
                     }
                 }
-            }
-            // The "Dolphin" complex resource takes Water + Life
-            ComplexResourceRequest::Dolphin(r1, r2) => {
-                let energy_cell = match state.full_cell() {
-                    Some((c, _)) => c,
-                    None => {
-                        let error_payload = Payload::from([
-                            ("error".to_string(), "no_energy_cell_available".to_string()),
-                            ("request_type".to_string(), "Dolphin".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            error_payload,
-                        )
-                        .emit();
+            };
+            self.deliver_deferred(request, outcome);
+        }
+    }
 
-                        return Err((
-                            "No energy cell available".to_string(),
-                            GenericResource::ComplexResources(ComplexResource::Water(r1)),
-                            GenericResource::ComplexResources(ComplexResource::Life(r2)),
-                        ));
-                    }
-                };
+    fn expire_deferred(&mut self, now: Instant) {
+        for request in self.waitlist.expire(now) {
+            self.deliver_deferred(request, DeferredOutcome::Expired);
+        }
+    }
 
-                let complex = combinator.make_dolphin(r1, r2, energy_cell);
+    fn cancel_deferred(&mut self, explorer_id: ID) {
+        for request in self.waitlist.cancel(explorer_id) {
+            self.deliver_deferred(request, DeferredOutcome::Cancelled);
+        }
+    }
 
-                match complex {
-                    Ok(complex) => {
-                        let success_payload = Payload::from([
-                            ("action".to_string(), "combine_success".to_string()),
-                            ("resource".to_string(), "Dolphin".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            success_payload,
-                        )
-                        .emit();
+    fn deliver_deferred(&self, request: DeferredRequest, outcome: DeferredOutcome) {
+        let mut payload = Payload::from([
+            ("action".to_string(), "deferred_request".to_string()),
+            ("explorer_id".to_string(), request.explorer_id.to_string()),
+            ("resource".to_string(), format!("{:?}", request.resource)),
+            ("outcome".to_string(), outcome.as_str().to_string()),
+        ]);
+        if let DeferredOutcome::Refused(reason) = &outcome {
+            payload.insert("reason".to_string(), reason.as_str().to_string());
+        }
+        let channel = if self.waitlist.deliver(request, outcome) {
+            Channel::Debug
+        } else {
+            payload.insert("error".to_string(), "delivery_channel_closed".to_string());
+            Channel::Warning
+        };
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::Explorer,
+                id: request.explorer_id,
+            }),
+            EventType::InternalExplorerAction,
+            channel,
+            payload,
+        )
+        .emit();
+    }
+
+    // Sends the post-mortem of the planet to the host, the asteroid is about to destroy it
+    fn report_destruction(&mut self, now: Instant) {
+        if !self.journal.is_enabled() {
+            return;
+        }
+        let cause = self.journal.cause(self.running);
+        let mut explorers = self.sessions.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        explorers.sort_unstable();
+        let risk = self.shared().risk.last();
+        let post_mortem = self.journal.post_mortem(self.planet_id, cause, explorers, risk, now);
+        let sent = self.journal.send(post_mortem);
+
+        let mut payload = Payload::from([
+            ("action".to_string(), "post_mortem".to_string()),
+            ("cause".to_string(), cause.as_str().to_string()),
+            ("sent".to_string(), sent.to_string()),
+        ]);
+        if let Some(rule) = cause.rule() {
+            payload.insert("rule".to_string(), rule.as_str().to_string());
+        }
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::SelfActor,
+                id: self.planet_id,
+            }),
+            EventType::InternalPlanetAction,
+            Channel::Warning,
+            payload,
+        )
+        .emit();
+    }
+
+    // Keeps the transaction in the ledger and bills the explorer for the resource it got
+    fn record_transaction(&mut self, transaction: Transaction) {
+        let explorer_id = transaction.explorer_id;
+        if transaction.outcome.is_success() {
+            self.sessions.record_served(explorer_id);
+        }
+        if let Some(resource) = transaction.output
+            && transaction.energy_cells > 0
+        {
+            self.journal.record(JournalEvent::CellSpent { explorer_id, resource }, transaction.at);
+        }
+        let charged = {
+            let mut shared = self.shared();
+            let charged = match transaction.output {
+                Some(output) if transaction.outcome.is_success() => {
+                    Some((output, shared.billing.charge(explorer_id, output)))
+                }
+                _ => None,
+            };
+            shared.ledger.record(transaction);
+            charged
+        };
+
+        if let Some((resource, credits)) = charged {
+            let payload = Payload::from([
+                ("action".to_string(), "explorer_charged".to_string()),
+                ("explorer_id".to_string(), explorer_id.to_string()),
+                ("resource".to_string(), format!("{:?}", resource)),
+                ("credits".to_string(), credits.to_string()),
+            ]);
+            LogEvent::new(
+                Some(Participant {
+                    actor_type: ActorType::Planet,
+                    id: self.planet_id,
+                }),
+                Some(Participant {
+                    actor_type: ActorType::Explorer,
+                    id: explorer_id,
+                }),
+                EventType::InternalExplorerAction,
+                Channel::Trace,
+                payload,
+            )
+            .emit();
+        }
+    }
+
+    // Estimated time before a charged cell is available, from the sunrays observed so far
+    fn energy_eta(&self, state: &PlanetState) -> Option<Duration> {
+        let charged_cell = state.cells_iter().any(|cell| cell.is_charged());
+        self.shared().sunrays.energy_eta(charged_cell, Instant::now())
+    }
+
+    // Publishes the energy available to the host, used by the quote API
+    fn sync_state(&self, state: &PlanetState) {
+        let mut shared = self.shared();
+        shared.charged_cell = state.cells_iter().any(|cell| cell.is_charged());
+        shared.stockpiled_carbon = self.stockpile.len();
+        shared.cache = self.cache.stats();
+    }
+
+    // Hands out a pre-crafted resource, keeping the explorer inputs as donations
+    fn serve_from_cache(
+        &mut self,
+        explorer_id: ID,
+        complex: ComplexResource,
+        request: ComplexResourceRequest,
+        now: Instant,
+    ) -> ComplexResource {
+        let (resource, r1, r2) = request_inputs(request);
+        let inputs = vec![r1.get_type(), r2.get_type()];
+        for input in [r1, r2] {
+            self.cache.donate(input);
+        }
+        self.record_transaction(Transaction {
+            at: now,
+            explorer_id,
+            kind: TransactionKind::Combine,
+            inputs,
+            output: Some(ResourceType::Complex(resource)),
+            energy_cells: 0,
+            outcome: TransactionOutcome::FromCache,
+        });
+
+        let payload = Payload::from([
+            ("action".to_string(), "served_from_cache".to_string()),
+            ("resource".to_string(), format!("{:?}", resource)),
+            ("explorer_id".to_string(), explorer_id.to_string()),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::Explorer,
+                id: explorer_id,
+            }),
+            EventType::InternalExplorerAction,
+            Channel::Debug,
+            payload,
+        )
+        .emit();
+        complex
+    }
+
+    // Pre-crafts the most wanted cached resource whose inputs are at hand. Returns true if it did.
+    fn precraft(&mut self, state: &mut PlanetState, combinator: &Combinator, now: Instant) -> bool {
+        if !self.cache.is_enabled() || state.full_cell().is_none() {
+            return false;
+        }
+
+        for resource in self.cache.targets() {
+            if !combinator.contains(resource) {
+                continue;
+            }
+            let Some((r1, r2)) = self.gather_inputs(resource) else {
+                continue;
+            };
+            let request = match combination_request(resource, r1, r2) {
+                Ok(request) => request,
+                Err((r1, r2)) => {
+                    self.return_inputs(r1, r2);
+                    continue;
+                }
+            };
+            let Some((cell, _)) = state.full_cell() else {
+                return false;
+            };
+
+            match combinator.try_make(request, cell) {
+                Ok(complex) => {
+                    let evicted = self.cache.insert(complex, now);
+                    let mut payload = Payload::from([
+                        ("action".to_string(), "precrafted".to_string()),
+                        ("resource".to_string(), format!("{:?}", resource)),
+                    ]);
+                    if let Some(evicted) = evicted {
+                        payload.insert("evicted".to_string(), format!("{:?}", evicted));
+                    }
+                    LogEvent::new(
+                        Some(Participant {
+                            actor_type: ActorType::Planet,
+                            id: self.planet_id,
+                        }),
+                        Some(Participant {
+                            actor_type: ActorType::SelfActor,
+                            id: self.planet_id,
+                        }),
+                        EventType::InternalPlanetAction,
+                        Channel::Debug,
+                        payload,
+                    )
+                    .emit();
+                    return true;
+                }
+                Err((e, r1, r2)) => {
+                    self.return_inputs(r1, r2);
+                    let payload = Payload::from([
+                        ("error".to_string(), "precraft_failed".to_string()),
+                        ("resource".to_string(), format!("{:?}", resource)),
+                        ("error_message".to_string(), e),
+                    ]);
+                    LogEvent::new(
+                        Some(Participant {
+                            actor_type: ActorType::Planet,
+                            id: self.planet_id,
+                        }),
+                        Some(Participant {
+                            actor_type: ActorType::SelfActor,
+                            id: self.planet_id,
+                        }),
+                        EventType::InternalPlanetAction,
+                        Channel::Warning,
+                        payload,
+                    )
+                    .emit();
+                }
+            }
+        }
+        false
+    }
+
+    // Takes the inputs of a recipe from the cache, then Carbon from the stockpile
+    fn gather_inputs(&mut self, resource: ComplexResourceType) -> Option<(GenericResource, GenericResource)> {
+        let (a, b) = recipe_inputs(resource);
+        let r1 = self.take_input(a)?;
+        match self.take_input(b) {
+            Some(r2) => Some((r1, r2)),
+            None => {
+                self.return_input(r1);
+                None
+            }
+        }
+    }
+
+    fn take_input(&mut self, input: ResourceType) -> Option<GenericResource> {
+        self.cache.take_input(input).or_else(|| match input {
+            ResourceType::Basic(BasicResourceType::Carbon) => {
+                self.stockpile.take().map(Carbon::to_generic)
+            }
+            _ => None,
+        })
+    }
+
+    // Keeps a resource paid to the planet within the stockpile and cache limits
+    fn keep_payment(&mut self, resource: GenericResource) -> Result<(), GenericResource> {
+        match resource.get_type() {
+            ResourceType::Basic(BasicResourceType::Carbon) => {
+                let has_room = self
+                    .config
+                    .carbon_stockpile
+                    .is_some_and(|capacity| self.stockpile.len() < capacity);
+                if !has_room {
+                    return Err(resource);
+                }
+                if let Ok(carbon) = resource.to_carbon() {
+                    self.stockpile.push(carbon);
+                }
+                Ok(())
+            }
+            _ => self.cache.accept(resource),
+        }
+    }
+
+    // Puts unused inputs back where they can be used again
+    fn return_inputs(&mut self, r1: GenericResource, r2: GenericResource) {
+        self.return_input(r1);
+        self.return_input(r2);
+    }
+
+    fn return_input(&mut self, input: GenericResource) {
+        match input.get_type() {
+            ResourceType::Basic(BasicResourceType::Carbon) => {
+                if let Ok(carbon) = input.to_carbon() {
+                    self.stockpile.push(carbon);
+                }
+            }
+            _ => self.cache.return_input(input),
+        }
+    }
+
+    // Turns the charged cell into Carbon if the stockpile has room. Returns true if it did.
+    fn stockpile_carbon(&mut self, state: &mut PlanetState, generator: &Generator) -> bool {
+        let Some(capacity) = self.config.carbon_stockpile else {
+            return false;
+        };
+        if self.stockpile.len() >= capacity {
+            return false;
+        }
+        let Some((cell, _)) = state.full_cell() else {
+            return false;
+        };
+
+        match generator.make_carbon(cell) {
+            Ok(carbon) => {
+                self.stockpile.push(carbon);
+                let payload = Payload::from([
+                    ("action".to_string(), "carbon_stockpiled".to_string()),
+                    ("stockpile".to_string(), self.stockpile.len().to_string()),
+                    ("capacity".to_string(), capacity.to_string()),
+                ]);
+                LogEvent::new(
+                    Some(Participant {
+                        actor_type: ActorType::Planet,
+                        id: self.planet_id,
+                    }),
+                    Some(Participant {
+                        actor_type: ActorType::SelfActor,
+                        id: self.planet_id,
+                    }),
+                    EventType::InternalPlanetAction,
+                    Channel::Debug,
+                    payload,
+                )
+                .emit();
+                true
+            }
+            Err(e) => {
+                let payload = Payload::from([
+                    ("error".to_string(), "stockpile_failed".to_string()),
+                    ("error_message".to_string(), e),
+                ]);
+                LogEvent::new(
+                    Some(Participant {
+                        actor_type: ActorType::Planet,
+                        id: self.planet_id,
+                    }),
+                    Some(Participant {
+                        actor_type: ActorType::SelfActor,
+                        id: self.planet_id,
+                    }),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                )
+                .emit();
+                false
+            }
+        }
+    }
+
+    // Checks whether the explorers on the planet are actually waiting for energy
+    fn explorer_demand(&self, now: Instant) -> ExplorerDemand {
+        if self.sessions.is_empty() {
+            return ExplorerDemand::NoExplorers;
+        }
+        // Low priority explorers never delay the defense of the planet
+        let all_low = {
+            let shared = self.shared();
+            self.sessions
+                .iter()
+                .all(|(explorer_id, _)| shared.priorities.class_of(*explorer_id) == PriorityClass::Low)
+        };
+        if all_low {
+            return ExplorerDemand::LowPriorityExplorers;
+        }
+        match self.config.demand_window {
+            None => ExplorerDemand::ExplorersPresent,
+            Some(window) if self.sessions.has_recent_demand(now, window) => {
+                ExplorerDemand::RecentDemand
+            }
+            Some(_) => match self.config.demand_forecast_threshold {
+                Some(threshold) if self.predicted_demand(now).probability >= threshold => {
+                    ExplorerDemand::PredictedDemand
+                }
+                _ => ExplorerDemand::IdleExplorers,
+            },
+        }
+    }
+
+    // Counts the sunray in the epoch of the adaptive policy, switching policy when the epoch ends
+    fn score_policy(&mut self, wasted: bool) {
+        let (closed, policy) = {
+            let mut shared = self.shared();
+            (shared.learner.record_sunray(wasted), shared.learner.policy())
+        };
+        if !closed {
+            return;
+        }
+
+        let payload = Payload::from([
+            ("action".to_string(), "adaptive_policy_epoch".to_string()),
+            ("next_policy".to_string(), policy.as_str().to_string()),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::SelfActor,
+                id: self.planet_id,
+            }),
+            EventType::InternalPlanetAction,
+            Channel::Info,
+            payload,
+        )
+        .emit();
+    }
+
+    // Risk of being hit by an asteroid without defense, in the current state of the planet
+    fn assess_risk(&self, state: &PlanetState, now: Instant) -> RiskAssessment {
+        let charged_cell = state.cells_iter().any(|cell| cell.is_charged());
+        self.shared()
+            .risk
+            .assess(state.has_rocket(), charged_cell, !self.sessions.is_empty(), now)
+    }
+
+    fn log_risk(&self, explorer_id: ID, risk: &RiskAssessment) {
+        let payload = Payload::from([
+            ("action".to_string(), "risk_assessment".to_string()),
+            ("explorer_id".to_string(), explorer_id.to_string()),
+            ("risk_score".to_string(), format!("{:.2}", risk.score)),
+            ("risk_level".to_string(), risk.level.as_str().to_string()),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::Explorer,
+                id: explorer_id,
+            }),
+            EventType::InternalExplorerAction,
+            Channel::Debug,
+            payload,
+        )
+        .emit();
+    }
+
+    // Keeps the last charged cell for an emergency rocket when there is no rocket and the risk is high
+    fn defense_guard(&self, explorer_id: ID, state: &PlanetState, now: Instant) -> Result<(), RefusalReason> {
+        let charged_cells = state.cells_iter().filter(|cell| cell.is_charged()).count();
+        if !self.config.risk.keep_cell_for_defense || state.has_rocket() || charged_cells != 1 {
+            return Ok(());
+        }
+        let risk = self.assess_risk(state, now);
+        if risk.level != RiskLevel::High {
+            return Ok(());
+        }
+
+        let reason = RefusalReason::KeptForDefense;
+        self.shared().priorities.record_refused(explorer_id, false);
+        let payload = Payload::from([
+            ("action".to_string(), "request_refused".to_string()),
+            ("reason".to_string(), reason.as_str().to_string()),
+            ("explorer_id".to_string(), explorer_id.to_string()),
+            ("risk_score".to_string(), format!("{:.2}", risk.score)),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::Explorer,
+                id: explorer_id,
+            }),
+            EventType::MessagePlanetToExplorer,
+            Channel::Warning,
+            payload,
+        )
+        .emit();
+        Err(reason)
+    }
+
+    // Builds a rocket rather than keeping the cell for the explorers when the risk is high
+    fn risk_guard(&self, demand: ExplorerDemand, risk: &RiskAssessment, state: &PlanetState) -> ExplorerDemand {
+        if risk.level == RiskLevel::High
+            && self.shared().risk.defends_when_high()
+            && demand.saves_energy()
+            && !state.has_rocket()
+        {
+            return ExplorerDemand::HighRisk;
+        }
+        demand
+    }
+
+    // Builds a rocket rather than keeping the cell for the explorers when the sunray model
+    // confidently expects a night before the next sunray
+    fn night_guard(&self, demand: ExplorerDemand, state: &PlanetState) -> ExplorerDemand {
+        let model = &self.config.sunray_model;
+        let Some(min_confidence) = model.defend_at_night else {
+            return demand;
+        };
+        if !demand.saves_energy() || state.has_rocket() {
+            return demand;
+        }
+        match self.shared().sunrays.pattern() {
+            Some(pattern)
+                if pattern.confidence >= min_confidence && pattern.night_ahead(model.night_factor) =>
+            {
+                ExplorerDemand::NightAhead
+            }
+            _ => demand,
+        }
+    }
+
+    // Returns true if an explorer of a higher class than the requester is on the planet and wants energy:
+    // it asked for some and wasn't served yet, within the demand window if one is configured.
+    fn higher_priority_waiting(&self, explorer_id: ID, now: Instant) -> bool {
+        let shared = self.shared();
+        let class = shared.priorities.class_of(explorer_id);
+        self.sessions.iter().any(|(other, session)| {
+            *other != explorer_id
+                && shared.priorities.class_of(*other) > class
+                && session.pending_demand.is_some_and(|at| {
+                    self.config
+                        .demand_window
+                        .is_none_or(|window| now.saturating_duration_since(at) <= window)
+                })
+        })
+    }
+
+    // Checks a cell request the host makes on behalf of an explorer, as a live request would be
+    fn check_hosted_request(&self, explorer_id: ID, resource: ResourceType, now: Instant) -> Result<(), RefusalReason> {
+        if self.shared().conduct.is_banned(explorer_id, now) {
+            return Err(RefusalReason::Blacklisted);
+        }
+        if self.higher_priority_waiting(explorer_id, now) {
+            return Err(RefusalReason::KeptForHigherPriority);
+        }
+        let mut shared = self.shared();
+        shared
+            .auction
+            .check_cell(explorer_id, now)
+            .and_then(|_| shared.quotas.check_cell(explorer_id, now))
+            .and_then(|_| shared.billing.check(explorer_id, resource))
+    }
+
+    /// Predicted energy demand of the explorers currently on the planet over the configured horizon
+    pub fn predicted_demand(&self, now: Instant) -> DemandForecast {
+        self.shared().demand.forecast_for(
+            self.sessions.iter().map(|(explorer_id, _)| explorer_id),
+            now,
+            self.config.demand_horizon,
+        )
+    }
+
+    fn shared(&self) -> MutexGuard<'_, SharedState> {
+        handle::lock(&self.shared)
+    }
+
+    // Answers a request the planet refuses to serve, giving the explorer its resources back
+    fn refuse(
+        &self,
+        explorer_id: ID,
+        msg_type: &str,
+        msg: ExplorerToPlanet,
+        reason: RefusalReason,
+    ) -> Option<PlanetToExplorer> {
+        let class = {
+            let mut shared = self.shared();
+            shared.priorities.record_refused(
+                explorer_id,
+                reason == RefusalReason::KeptForHigherPriority,
+            );
+            shared.priorities.class_of(explorer_id)
+        };
+
+        let payload = Payload::from([
+            ("action".to_string(), "request_refused".to_string()),
+            ("reason".to_string(), reason.as_str().to_string()),
+            ("message_type".to_string(), msg_type.to_string()),
+            ("explorer_id".to_string(), explorer_id.to_string()),
+            ("priority".to_string(), class.as_str().to_string()),
+        ]);
+        LogEvent::new(
+            Some(Participant {
+                actor_type: ActorType::Planet,
+                id: self.planet_id,
+            }),
+            Some(Participant {
+                actor_type: ActorType::Explorer,
+                id: explorer_id,
+            }),
+            EventType::MessagePlanetToExplorer,
+            Channel::Warning,
+            payload,
+        )
+        .emit();
+
+        match msg {
+            ExplorerToPlanet::GenerateResourceRequest { .. } => {
+                Some(PlanetToExplorer::GenerateResourceResponse { resource: None })
+            }
+            ExplorerToPlanet::CombineResourceRequest { msg, .. } => {
+                let (_, r1, r2) = request_inputs(msg);
+                Some(PlanetToExplorer::CombineResourceResponse {
+                    complex_response: Err((reason.message(), r1, r2)),
+                })
+            }
+            // Informational requests get an empty answer, so explorers waiting for one don't hang
+            ExplorerToPlanet::SupportedResourceRequest { .. } => {
+                Some(PlanetToExplorer::SupportedResourceResponse {
+                    resource_list: Default::default(),
+                })
+            }
+            ExplorerToPlanet::SupportedCombinationRequest { .. } => {
+                Some(PlanetToExplorer::SupportedCombinationResponse {
+                    combination_list: Default::default(),
+                })
+            }
+            ExplorerToPlanet::AvailableEnergyCellRequest { .. } => {
+                Some(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 0 })
+            }
+        }
+    }
+
+    fn has_charged_cells(&self, state: &mut PlanetState) -> bool {
+        //Enterprise (planet of type C) support only 1 energy cell
+        state.full_cell().is_some()
+    }
+
+    // Returns the resource and whether a charged cell was used for it
+    fn handle_resource_request(
+        &mut self,
+        explorer_id: u32,
+        request: BasicResourceType,
+        generator: &Generator,
+        state: &mut PlanetState,
+        now: Instant,
+    ) -> Option<(BasicResource, bool)> {
+        let mut transaction = Transaction {
+            at: now,
+            explorer_id,
+            kind: TransactionKind::Generate,
+            inputs: Vec::new(),
+            output: None,
+            energy_cells: 0,
+            outcome: TransactionOutcome::Generated,
+        };
+
+        if !generator.contains(request) {
+            let payload = Payload::from([
+                ("error".to_string(), "unsupported_resource".to_string()),
+                ("requested_resource".to_string(), format!("{:?}", request)),
+            ]);
+            LogEvent::new(
+                Some(Participant {
+                    actor_type: ActorType::Planet,
+                    id: self.planet_id,
+                }),
+                Some(Participant {
+                    actor_type: ActorType::SelfActor,
+                    id: self.planet_id,
+                }),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            )
+            .emit();
+            transaction.outcome = TransactionOutcome::Failed("Unsupported resource".to_string());
+            self.record_transaction(transaction);
+            return None;
+        } else {
+            // Stockpiled carbon is handed out first, so the live cell stays available for defense
+            if matches!(request, BasicResourceType::Carbon)
+                && let Some(carbon) = self.stockpile.take()
+            {
+                let payload = Payload::from([
+                    ("action".to_string(), "resource_from_stockpile".to_string()),
+                    ("resource".to_string(), "Carbon".to_string()),
+                    ("stockpile".to_string(), self.stockpile.len().to_string()),
+                ]);
+                LogEvent::new(
+                    Some(Participant {
+                        actor_type: ActorType::Planet,
+                        id: self.planet_id,
+                    }),
+                    Some(Participant {
+                        actor_type: ActorType::SelfActor,
+                        id: self.planet_id,
+                    }),
+                    EventType::InternalPlanetAction,
+                    Channel::Debug,
+                    payload,
+                )
+                .emit();
+                transaction.output = Some(ResourceType::Basic(request));
+                transaction.outcome = TransactionOutcome::FromStockpile;
+                self.record_transaction(transaction);
+                return Some((BasicResource::Carbon(carbon), false));
+            }
+
+            // The explorer only learns the reason through the ledger, the response can't carry it
+            if let Err(reason) = self.defense_guard(explorer_id, state, now) {
+                transaction.outcome = TransactionOutcome::Failed(reason.message());
+                self.record_transaction(transaction);
+                return None;
+            }
+
+            let energy_cell = match state.full_cell() {
+                Some((c, i)) => {
+                    let payload = Payload::from([
+                        ("action".to_string(), "using_charged_cell".to_string()),
+                        ("cell_index".to_string(), i.to_string()),
+                    ]);
+                    LogEvent::new(
+                        Some(Participant {
+                            actor_type: ActorType::Planet,
+                            id: self.planet_id,
+                        }),
+                        Some(Participant {
+                            actor_type: ActorType::SelfActor,
+                            id: self.planet_id,
+                        }),
+                        EventType::InternalPlanetAction,
+                        Channel::Trace,
+                        payload,
+                    )
+                    .emit();
+                    c
+                }
+                None => {
+                    let payload =
+                        Payload::from([("error".to_string(), "no_charged_cell".to_string())]);
+                    LogEvent::new(
+                        Some(Participant {
+                            actor_type: ActorType::Planet,
+                            id: self.planet_id,
+                        }),
+                        Some(Participant {
+                            actor_type: ActorType::SelfActor,
+                            id: self.planet_id,
+                        }),
+                        EventType::InternalPlanetAction,
+                        Channel::Debug,
+                        payload,
+                    )
+                    .emit();
+                    transaction.outcome = TransactionOutcome::Failed("No charged energy cell".to_string());
+                    self.record_transaction(transaction);
+                    self.shared().learner.record_unserved();
+                    return None;
+                }
+            };
 
-                        Ok(ComplexResource::Dolphin(complex))
-                    }
-                    Err((s, r1, r2)) => {
-                        let fail_payload = Payload::from([
-                            ("error".to_string(), "combine_failed".to_string()),
-                            ("request_type".to_string(), "Dolphin".to_string()),
-                            ("error_message".to_string(), s.clone()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            fail_payload,
-                        )
-                        .emit();
+            let new_resource = generator
+                .make_carbon(energy_cell)
+                .map(BasicResource::Carbon);
 
-                        Err((
-                            s,
-                            GenericResource::ComplexResources(ComplexResource::Water(r1)),
-                            GenericResource::ComplexResources(ComplexResource::Life(r2)),
-                        ))
-                    }
+            match new_resource {
+                Ok(new_resource) => {
+                    let payload = Payload::from([
+                        ("action".to_string(), "resource_generated".to_string()),
+                        ("resource".to_string(), "Carbon".to_string()),
+                    ]);
+                    LogEvent::new(
+                        Some(Participant {
+                            actor_type: ActorType::Planet,
+                            id: self.planet_id,
+                        }),
+                        Some(Participant {
+                            actor_type: ActorType::SelfActor,
+                            id: self.planet_id,
+                        }),
+                        EventType::InternalPlanetAction,
+                        Channel::Debug,
+                        payload,
+                    )
+                    .emit();
+                    transaction.output = Some(ResourceType::Basic(request));
+                    transaction.energy_cells = 1;
+                    self.record_transaction(transaction);
+                    return Some((new_resource, true));
                 }
-            }
-            // The "Life" complex resource takes Water + Carbon
-            ComplexResourceRequest::Life(r1, r2) => {
-                let energy_cell = match state.full_cell() {
-                    Some((c, _)) => c,
-                    None => {
-                        let error_payload = Payload::from([
-                            ("error".to_string(), "no_energy_cell_available".to_string()),
-                            ("request_type".to_string(), "Life".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            error_payload,
-                        )
-                        .emit();
-
-                        return Err((
-                            "No energy cell available".to_string(),
-                            GenericResource::ComplexResources(ComplexResource::Water(r1)),
-                            GenericResource::BasicResources(BasicResource::Carbon(r2)),
-                        ));
-                    }
-                };
+                Err(e) => {
+                    let payload = Payload::from([
+                        ("error".to_string(), "generation_failed".to_string()),
+                        ("error_message".to_string(), e.clone()),
+                    ]);
+                    LogEvent::new(
+                        Some(Participant {
+                            actor_type: ActorType::Planet,
+                            id: self.planet_id,
+                        }),
+                        Some(Participant {
+                            actor_type: ActorType::SelfActor,
+                            id: self.planet_id,
+                        }),
+                        EventType::InternalPlanetAction,
+                        Channel::Debug,
+                        payload,
+                    )
+                    .emit();
+                    transaction.outcome = TransactionOutcome::Failed(e);
+                    self.record_transaction(transaction);
+                }
+            };
+        }
+        None
+    }
 
-                let complex = combinator.make_life(r1, r2, energy_cell);
+    fn handle_combine_request(
+        &mut self,
+        explorer_id: u32,
+        request: ComplexResourceRequest,
+        combinator: &Combinator,
+        state: &mut PlanetState,
+        now: Instant,
+    ) -> Result<ComplexResource, (String, GenericResource, GenericResource)> {
+        let resource = requested_type(&request);
+        let request_type = format!("{:?}", resource);
+        let (a, b) = request_input_types(&request);
+        let mut transaction = Transaction {
+            at: now,
+            explorer_id,
+            kind: TransactionKind::Combine,
+            inputs: vec![a, b],
+            output: None,
+            energy_cells: 0,
+            outcome: TransactionOutcome::Generated,
+        };
 
-                match complex {
-                    Ok(complex) => {
-                        let success_payload = Payload::from([
-                            ("action".to_string(), "combine_success".to_string()),
-                            ("resource".to_string(), "Life".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            success_payload,
-                        )
-                        .emit();
+        // Nothing is touched until the request is known to be valid
+        let charged_cell = state.full_cell().map(|(_, at)| at);
+        let (request, at) = match validate_combination(request, combinator, charged_cell) {
+            Ok(valid) => valid,
+            Err((rejection, r1, r2)) => {
+                if rejection == CombineRejection::NoEnergyCell {
+                    self.shared().learner.record_unserved();
+                }
+                let error_payload = Payload::from([
+                    ("error".to_string(), rejection.as_str().to_string()),
+                    ("request_type".to_string(), request_type),
+                    ("explorer_id".to_string(), explorer_id.to_string()),
+                ]);
 
-                        Ok(ComplexResource::Life(complex))
-                    }
-                    Err((s, r1, r2)) => {
-                        let fail_payload = Payload::from([
-                            ("error".to_string(), "combine_failed".to_string()),
-                            ("request_type".to_string(), "Life".to_string()),
-                            ("error_message".to_string(), s.clone()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            fail_payload,
-                        )
-                        .emit();
+                LogEvent::new(
+                    Some(Participant {
+                        actor_type: ActorType::Planet,
+                        id: self.planet_id,
+                    }),
+                    Some(Participant {
+                        actor_type: ActorType::SelfActor,
+                        id: self.planet_id,
+                    }),
+                    EventType::InternalPlanetAction,
+                    Channel::Debug,
+                    error_payload,
+                )
+                .emit();
 
-                        Err((
-                            s,
-                            GenericResource::ComplexResources(ComplexResource::Water(r1)),
-                            GenericResource::BasicResources(BasicResource::Carbon(r2)),
-                        ))
-                    }
-                }
+                transaction.outcome = TransactionOutcome::Failed(rejection.message());
+                self.record_transaction(transaction);
+                return Err((rejection.message(), r1, r2));
             }
-            // The "Robot" complex resource takes Silicon + Life
-            ComplexResourceRequest::Robot(r1, r2) => {
-                let energy_cell = match state.full_cell() {
-                    Some((c, _)) => c,
-                    None => {
-                        let error_payload = Payload::from([
-                            ("error".to_string(), "no_energy_cell_available".to_string()),
-                            ("request_type".to_string(), "Robot".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            error_payload,
-                        )
-                        .emit();
-
-                        return Err((
-                            "No energy cell available".to_string(),
-                            GenericResource::BasicResources(BasicResource::Silicon(r1)),
-                            GenericResource::ComplexResources(ComplexResource::Life(r2)),
-                        ));
-                    }
-                };
+        };
+        if let Err(reason) = self.defense_guard(explorer_id, state, now) {
+            let (_, r1, r2) = request_inputs(request);
+            transaction.outcome = TransactionOutcome::Failed(reason.message());
+            self.record_transaction(transaction);
+            return Err((reason.message(), r1, r2));
+        }
 
-                let complex = combinator.make_robot(r1, r2, energy_cell);
+        match combinator.try_make(request, state.cell_mut(at)) {
+            Ok(complex) => {
+                let success_payload = Payload::from([
+                    ("action".to_string(), "combine_success".to_string()),
+                    ("resource".to_string(), request_type),
+                    ("explorer_id".to_string(), explorer_id.to_string()),
+                ]);
 
-                match complex {
-                    Ok(complex) => {
-                        let success_payload = Payload::from([
-                            ("action".to_string(), "combine_success".to_string()),
-                            ("resource".to_string(), "Robot".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            success_payload,
-                        )
-                        .emit();
+                LogEvent::new(
+                    Some(Participant {
+                        actor_type: ActorType::Planet,
+                        id: self.planet_id,
+                    }),
+                    Some(Participant {
+                        actor_type: ActorType::SelfActor,
+                        id: self.planet_id,
+                    }),
+                    EventType::InternalPlanetAction,
+                    Channel::Debug,
+                    success_payload,
+                )
+                .emit();
 
-                        Ok(ComplexResource::Robot(complex))
-                    }
-                    Err((s, r1, r2)) => {
-                        let fail_payload = Payload::from([
-                            ("error".to_string(), "combine_failed".to_string()),
-                            ("request_type".to_string(), "Robot".to_string()),
-                            ("error_message".to_string(), s.clone()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            fail_payload,
-                        )
-                        .emit();
+                transaction.output = Some(ResourceType::Complex(resource));
+                transaction.energy_cells = 1;
+                self.record_transaction(transaction);
+                Ok(complex)
+            }
+            Err((s, r1, r2)) => {
+                let fail_payload = Payload::from([
+                    ("error".to_string(), "combine_failed".to_string()),
+                    ("request_type".to_string(), request_type),
+                    ("error_message".to_string(), s.clone()),
+                    ("explorer_id".to_string(), explorer_id.to_string()),
+                ]);
+                LogEvent::new(
+                    Some(Participant {
+                        actor_type: ActorType::Planet,
+                        id: self.planet_id,
+                    }),
+                    Some(Participant {
+                        actor_type: ActorType::SelfActor,
+                        id: self.planet_id,
+                    }),
+                    EventType::InternalPlanetAction,
+                    Channel::Debug,
+                    fail_payload,
+                )
+                .emit();
 
-                        Err((
-                            s,
-                            GenericResource::BasicResources(BasicResource::Silicon(r1)),
-                            GenericResource::ComplexResources(ComplexResource::Life(r2)),
-                        ))
-                    }
-                }
+                transaction.outcome = TransactionOutcome::Failed(s.clone());
+                self.record_transaction(transaction);
+                Err((s, r1, r2))
             }
-            // The "Water" complex resource takes Hydrogen + Oxygen
-            ComplexResourceRequest::Water(r1, r2) => {
-                let energy_cell = match state.full_cell() {
-                    Some((c, _)) => c,
-                    None => {
-                        let error_payload = Payload::from([
-                            ("error".to_string(), "no_energy_cell_available".to_string()),
-                            ("request_type".to_string(), "Water".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            error_payload,
-                        )
-                        .emit();
+        }
+    }
+}
 
-                        return Err((
-                            "No energy cell available".to_string(),
-                            GenericResource::BasicResources(BasicResource::Hydrogen(r1)),
-                            GenericResource::BasicResources(BasicResource::Oxygen(r2)),
-                        ));
-                    }
-                };
+// Resource requested by the explorer that the planet can't generate or combine, if any
+fn unsupported_resource(
+    msg: &ExplorerToPlanet,
+    generator: &Generator,
+    combinator: &Combinator,
+) -> Option<ResourceType> {
+    match msg {
+        ExplorerToPlanet::GenerateResourceRequest { resource, .. } => {
+            (!generator.contains(*resource)).then_some(ResourceType::Basic(*resource))
+        }
+        ExplorerToPlanet::CombineResourceRequest { msg, .. } => {
+            let complex = requested_type(msg);
+            (!combinator.contains(complex)).then_some(ResourceType::Complex(complex))
+        }
+        _ => None,
+    }
+}
 
-                let complex = combinator.make_water(r1, r2, energy_cell);
+// Resource a generation or combination request asks for, if the planet can produce it
+fn requested_resource(
+    msg: &ExplorerToPlanet,
+    generator: &Generator,
+    combinator: &Combinator,
+) -> Option<ResourceType> {
+    let resource = match msg {
+        ExplorerToPlanet::GenerateResourceRequest { resource, .. } => ResourceType::Basic(*resource),
+        ExplorerToPlanet::CombineResourceRequest { msg, .. } => ResourceType::Complex(requested_type(msg)),
+        _ => return None,
+    };
+    unsupported_resource(msg, generator, combinator)
+        .is_none()
+        .then_some(resource)
+}
 
-                match complex {
-                    Ok(complex) => {
-                        let success_payload = Payload::from([
-                            ("action".to_string(), "combine_success".to_string()),
-                            ("resource".to_string(), "Water".to_string()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Debug,
-                            success_payload,
-                        )
-                        .emit();
+pub(crate) fn requested_type(request: &ComplexResourceRequest) -> ComplexResourceType {
+    match request {
+        ComplexResourceRequest::Water(..) => ComplexResourceType::Water,
+        ComplexResourceRequest::Diamond(..) => ComplexResourceType::Diamond,
+        ComplexResourceRequest::Life(..) => ComplexResourceType::Life,
+        ComplexResourceRequest::Robot(..) => ComplexResourceType::Robot,
+        ComplexResourceRequest::Dolphin(..) => ComplexResourceType::Dolphin,
+        ComplexResourceRequest::AIPartner(..) => ComplexResourceType::AIPartner,
+    }
+}
 
-                        Ok(ComplexResource::Water(complex))
-                    }
-                    Err((s, r1, r2)) => {
-                        let fail_payload = Payload::from([
-                            ("error".to_string(), "combine_failed".to_string()),
-                            ("request_type".to_string(), "Water".to_string()),
-                            ("error_message".to_string(), s.clone()),
-                        ]);
-                        LogEvent::new(
-                            Some(Participant {
-                                actor_type: ActorType::Planet,
-                                id: self.planet_id,
-                            }),
-                            Some(Participant {
-                                actor_type: ActorType::SelfActor,
-                                id: self.planet_id,
-                            }),
-                            EventType::InternalPlanetAction,
-                            Channel::Error,
-                            fail_payload,
-                        )
-                        .emit();
+// Types of the inputs carried by a combination request
+pub(crate) fn request_input_types(request: &ComplexResourceRequest) -> (ResourceType, ResourceType) {
+    match request {
+        ComplexResourceRequest::Water(r1, r2) => (
+            ResourceType::Basic(r1.to_basic_type()),
+            ResourceType::Basic(r2.to_basic_type()),
+        ),
+        ComplexResourceRequest::Diamond(r1, r2) => (
+            ResourceType::Basic(r1.to_basic_type()),
+            ResourceType::Basic(r2.to_basic_type()),
+        ),
+        ComplexResourceRequest::Life(r1, r2) => (
+            ResourceType::Complex(r1.to_complex_type()),
+            ResourceType::Basic(r2.to_basic_type()),
+        ),
+        ComplexResourceRequest::Robot(r1, r2) => (
+            ResourceType::Basic(r1.to_basic_type()),
+            ResourceType::Complex(r2.to_complex_type()),
+        ),
+        ComplexResourceRequest::Dolphin(r1, r2) => (
+            ResourceType::Complex(r1.to_complex_type()),
+            ResourceType::Complex(r2.to_complex_type()),
+        ),
+        ComplexResourceRequest::AIPartner(r1, r2) => (
+            ResourceType::Complex(r1.to_complex_type()),
+            ResourceType::Complex(r2.to_complex_type()),
+        ),
+    }
+}
 
-                        Err((
-                            s,
-                            GenericResource::BasicResources(BasicResource::Hydrogen(r1)),
-                            GenericResource::BasicResources(BasicResource::Oxygen(r2)),
-                        ))
-                    }
-                }
-            }
+// Splits a combination request into the requested resource and its two inputs
+pub(crate) fn request_inputs(
+    request: ComplexResourceRequest,
+) -> (ComplexResourceType, GenericResource, GenericResource) {
+    match request {
+        ComplexResourceRequest::Water(r1, r2) => {
+            (ComplexResourceType::Water, r1.to_generic(), r2.to_generic())
+        }
+        ComplexResourceRequest::Diamond(r1, r2) => {
+            (ComplexResourceType::Diamond, r1.to_generic(), r2.to_generic())
+        }
+        ComplexResourceRequest::Life(r1, r2) => {
+            (ComplexResourceType::Life, r1.to_generic(), r2.to_generic())
+        }
+        ComplexResourceRequest::Robot(r1, r2) => {
+            (ComplexResourceType::Robot, r1.to_generic(), r2.to_generic())
+        }
+        ComplexResourceRequest::Dolphin(r1, r2) => {
+            (ComplexResourceType::Dolphin, r1.to_generic(), r2.to_generic())
+        }
+        ComplexResourceRequest::AIPartner(r1, r2) => {
+            (ComplexResourceType::AIPartner, r1.to_generic(), r2.to_generic())
         }
     }
-
 }
 
 /// Function used to create an Enterprise planet. It panics if it was not possible to create the planet
@@ -1391,17 +2553,23 @@ pub fn create_planet(
         tx_orchestrator: Sender<PlanetToOrchestrator>,
         rx_explorer: Receiver<ExplorerToPlanet>,
     ) -> Planet {
-        let id = id; // The planet should use the id that was given as a parameter during its creation
-        let ai = Box::new(EnterpriseAi::new(id));
-        let gen_rules = vec![BasicResourceType::Carbon];
-        let comb_rules = vec![
-            ComplexResourceType::Water,
-            ComplexResourceType::Diamond,
-            ComplexResourceType::Life,
-            ComplexResourceType::Robot,
-            ComplexResourceType::Dolphin,
-            ComplexResourceType::AIPartner,
-        ];
+        create_planet_with_config(id, rx_orchestrator, tx_orchestrator, rx_explorer, EnterpriseConfig::default()).0
+    }
+
+/// Same as [create_planet], but lets the caller tune the AI through an [EnterpriseConfig].
+/// It also returns the [EnterpriseHandle] used to inspect the AI while the planet is running
+pub fn create_planet_with_config(
+        id: u32,
+        rx_orchestrator: Receiver<OrchestratorToPlanet>,
+        tx_orchestrator: Sender<PlanetToOrchestrator>,
+        rx_explorer: Receiver<ExplorerToPlanet>,
+        config: EnterpriseConfig,
+    ) -> (Planet, EnterpriseHandle) {
+        // The planet uses the id that was given as a parameter during its creation
+        let ai = Box::new(EnterpriseAi::with_config(id, config));
+        let handle = ai.handle();
+        let gen_rules = recipes::generation_rules();
+        let comb_rules = recipes::combination_rules();
 
         match Planet::new(
             id,
@@ -1421,18 +2589,18 @@ pub fn create_planet(
                 LogEvent::new(
                             Some(Participant {
                                 actor_type: ActorType::Planet,
-                                id: id,
+                                id,
                             }),
                             Some(Participant {
                                 actor_type: ActorType::SelfActor,
-                                id: id,
+                                id,
                             }),
                             EventType::InternalPlanetAction,
                             Channel::Info,
                             payload,
                         )
                         .emit();
-                return planet;},
+                (planet, handle)},
             Err(error) => {
                 let payload = Payload::from([
                             ("action".to_string(), "create_planet".to_string()),
@@ -1443,11 +2611,11 @@ pub fn create_planet(
                 LogEvent::new(
                             Some(Participant {
                                 actor_type: ActorType::Planet,
-                                id: id,
+                                id,
                             }),
                             Some(Participant {
                                 actor_type: ActorType::SelfActor,
-                                id: id,
+                                id,
                             }),
                             EventType::InternalPlanetAction,
                             Channel::Error,
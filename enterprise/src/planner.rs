@@ -0,0 +1,106 @@
+use crate::recipes::{recipe_inputs, Recipes};
+use common_game::components::resource::{BasicResourceType, ComplexResourceType, ResourceType};
+use std::collections::HashMap;
+
+/// Single action the planet performs in a crafting plan, each one takes a charged cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CraftStep {
+    Generate(BasicResourceType),
+    Combine(ComplexResourceType),
+}
+
+/// Ordered steps needed to obtain a resource, starting from an inventory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CraftPlan {
+    pub target: ResourceType,
+    pub steps: Vec<CraftStep>, // Inputs always come before the combination using them
+    pub energy_cells: u32,     // Charged cells the planet needs for the whole plan
+    pub from_inventory: HashMap<ResourceType, u32>, // Inventory resources the plan consumes
+}
+
+/// Why no plan exists for a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanError {
+    Unobtainable(ResourceType), // Not in the inventory and the planet can't produce it
+}
+
+/// Dependency graph of the resources a planet can produce
+#[derive(Debug, Clone)]
+pub struct CraftPlanner {
+    recipes: Recipes,
+}
+
+impl CraftPlanner {
+    pub fn new(recipes: Recipes) -> Self {
+        Self { recipes }
+    }
+
+    // Planner over the recipes Enterprise is created with
+    pub fn enterprise() -> Self {
+        Self::new(Recipes::enterprise())
+    }
+
+    pub fn can_produce(&self, resource: ResourceType) -> bool {
+        match resource {
+            ResourceType::Basic(basic) => self.recipes.generation.contains(&basic),
+            ResourceType::Complex(complex) => self.recipes.combination.contains(&complex),
+        }
+    }
+
+    // Inputs of a resource in the graph, `None` for basic resources and disabled recipes
+    pub fn dependencies(&self, resource: ResourceType) -> Option<(ResourceType, ResourceType)> {
+        match resource {
+            ResourceType::Complex(complex) if self.recipes.combination.contains(&complex) => {
+                Some(recipe_inputs(complex))
+            }
+            _ => None,
+        }
+    }
+
+    // Steps to obtain the target, using the inventory first and the planet recipes for the rest
+    pub fn plan(
+        &self,
+        target: ResourceType,
+        inventory: &HashMap<ResourceType, u32>,
+    ) -> Result<CraftPlan, PlanError> {
+        let mut plan = CraftPlan {
+            target,
+            steps: Vec::new(),
+            energy_cells: 0,
+            from_inventory: HashMap::new(),
+        };
+        let mut inventory = inventory.clone();
+        self.require(target, &mut inventory, &mut plan)?;
+        plan.energy_cells = u32::try_from(plan.steps.len()).unwrap_or(u32::MAX);
+        Ok(plan)
+    }
+
+    fn require(
+        &self,
+        resource: ResourceType,
+        inventory: &mut HashMap<ResourceType, u32>,
+        plan: &mut CraftPlan,
+    ) -> Result<(), PlanError> {
+        if let Some(count) = inventory.get_mut(&resource)
+            && *count > 0
+        {
+            *count -= 1;
+            *plan.from_inventory.entry(resource).or_default() += 1;
+            return Ok(());
+        }
+
+        match resource {
+            ResourceType::Basic(basic) if self.recipes.generation.contains(&basic) => {
+                plan.steps.push(CraftStep::Generate(basic));
+            }
+            ResourceType::Complex(complex) if self.recipes.combination.contains(&complex) => {
+                let (a, b) = recipe_inputs(complex);
+                self.require(a, inventory, plan)?;
+                self.require(b, inventory, plan)?;
+                plan.steps.push(CraftStep::Combine(complex));
+            }
+            _ => return Err(PlanError::Unobtainable(resource)),
+        }
+        Ok(())
+    }
+}
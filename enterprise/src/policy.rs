@@ -0,0 +1,39 @@
+/// Outcome of the check on whether the explorers on the planet are waiting for energy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorerDemand {
+    NoExplorers,          // Nobody on the planet, the energy goes to defense
+    ExplorersPresent,     // Explorers on the planet and no demand window configured
+    RecentDemand,         // At least one explorer asked for energy within the demand window
+    PredictedDemand,      // No recent request, but the demand model expects one soon
+    IdleExplorers,        // Explorers on the planet, but none of them asked for energy lately
+    LowPriorityExplorers, // Only low priority explorers on the planet, the energy goes to defense
+    NightAhead,           // No rocket and a long gap before the next sunray, the energy goes to defense
+    DefenseFirst,         // No rocket and the adaptive policy puts defense first
+    HighRisk,             // No rocket and a high risk of asteroids, the energy goes to defense
+}
+
+impl ExplorerDemand {
+    // Returns true if the charged cell should be kept for the explorers instead of building a rocket
+    pub fn saves_energy(self) -> bool {
+        matches!(
+            self,
+            ExplorerDemand::ExplorersPresent
+                | ExplorerDemand::RecentDemand
+                | ExplorerDemand::PredictedDemand
+        )
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExplorerDemand::NoExplorers => "no_explorers",
+            ExplorerDemand::ExplorersPresent => "explorers_present",
+            ExplorerDemand::RecentDemand => "recent_explorer_demand",
+            ExplorerDemand::PredictedDemand => "predicted_explorer_demand",
+            ExplorerDemand::IdleExplorers => "idle_explorers",
+            ExplorerDemand::LowPriorityExplorers => "low_priority_explorers",
+            ExplorerDemand::NightAhead => "night_ahead",
+            ExplorerDemand::DefenseFirst => "defense_first",
+            ExplorerDemand::HighRisk => "high_risk",
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::policy::ExplorerDemand;
+use crate::recipes::resource_name;
+use crate::risk::RiskAssessment;
+use common_game::components::resource::ResourceType;
+use common_game::utils::ID;
+use crossbeam_channel::Sender;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Post-mortem written when the planet is destroyed
+#[derive(Debug, Clone)]
+pub struct PostMortemConfig {
+    pub sink: Option<Sender<PostMortem>>, // Where the host receives the report, `None` disables it
+    pub max_events: usize,                // Recent events kept for the report
+}
+
+impl Default for PostMortemConfig {
+    fn default() -> Self {
+        Self {
+            sink: None,
+            max_events: 32,
+        }
+    }
+}
+
+/// Something that happened on the planet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanetEvent {
+    Sunray {
+        decision: ExplorerDemand, // Outcome of the energy policy
+        used: bool,               // The sunray charged the cell
+        rocket_built: bool,
+    },
+    Asteroid {
+        defended: bool,
+    },
+    ExplorerArrived {
+        explorer_id: ID,
+    },
+    ExplorerLeft {
+        explorer_id: ID,
+    },
+    CellSpent {
+        explorer_id: ID,
+        resource: ResourceType,
+    },
+}
+
+impl PlanetEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanetEvent::Sunray { .. } => "sunray",
+            PlanetEvent::Asteroid { .. } => "asteroid",
+            PlanetEvent::ExplorerArrived { .. } => "explorer_arrived",
+            PlanetEvent::ExplorerLeft { .. } => "explorer_left",
+            PlanetEvent::CellSpent { .. } => "cell_spent",
+        }
+    }
+
+    fn is_energy_decision(&self) -> bool {
+        matches!(self, PlanetEvent::Sunray { .. } | PlanetEvent::CellSpent { .. })
+    }
+
+    fn to_json(self) -> String {
+        match self {
+            PlanetEvent::Sunray {
+                decision,
+                used,
+                rocket_built,
+            } => format!(
+                "\"decision\": \"{}\", \"used\": {}, \"rocket_built\": {}",
+                decision.as_str(),
+                used,
+                rocket_built
+            ),
+            PlanetEvent::Asteroid { defended } => format!("\"defended\": {}", defended),
+            PlanetEvent::ExplorerArrived { explorer_id } | PlanetEvent::ExplorerLeft { explorer_id } => {
+                format!("\"explorer_id\": {}", explorer_id)
+            }
+            PlanetEvent::CellSpent {
+                explorer_id,
+                resource,
+            } => format!(
+                "\"explorer_id\": {}, \"resource\": \"{}\"",
+                explorer_id,
+                resource_name(resource)
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedEvent {
+    pub at: Instant,
+    pub event: PlanetEvent,
+}
+
+/// Which rule left the planet without a rocket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefenseGap {
+    PlanetStopped, // The AI was stopped and doesn't defend the planet
+    NoSunray,      // No sunray reached the planet since the last rocket was launched
+    // The energy was kept for the explorers by the rule, and the last cell was spent by one of them
+    CellSpentByExplorer {
+        explorer_id: ID,
+        rule: Option<ExplorerDemand>,
+    },
+    // Sunrays came, but the rule didn't turn them into a rocket
+    RocketNotBuilt {
+        rule: ExplorerDemand,
+    },
+}
+
+impl DefenseGap {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DefenseGap::PlanetStopped => "planet_stopped",
+            DefenseGap::NoSunray => "no_sunray_since_last_rocket",
+            DefenseGap::CellSpentByExplorer { .. } => "last_cell_spent_by_explorer",
+            DefenseGap::RocketNotBuilt { .. } => "rocket_not_built",
+        }
+    }
+
+    // Energy policy rule behind the gap, if any
+    pub fn rule(&self) -> Option<ExplorerDemand> {
+        match self {
+            DefenseGap::CellSpentByExplorer { rule, .. } => *rule,
+            DefenseGap::RocketNotBuilt { rule } => Some(*rule),
+            _ => None,
+        }
+    }
+}
+
+/// Report on the destruction of the planet, sent to the host
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostMortem {
+    pub planet_id: u32,
+    pub at: Instant,
+    pub cause: DefenseGap,
+    pub explorers_present: Vec<ID>,
+    pub energy_decisions: Vec<TimedEvent>, // Since the last rocket was launched, oldest first
+    pub recent_events: Vec<TimedEvent>,    // Oldest first
+    pub risk: Option<RiskAssessment>,      // Last risk assessment before the asteroid
+}
+
+impl PostMortem {
+    fn events_to_json(&self, events: &[TimedEvent]) -> String {
+        let events = events
+            .iter()
+            .map(|timed| {
+                format!(
+                    "    {{\"ms_before\": {}, \"event\": \"{}\", {}}}",
+                    self.at.saturating_duration_since(timed.at).as_millis(),
+                    timed.event.as_str(),
+                    timed.event.to_json()
+                )
+            })
+            .collect::<Vec<_>>();
+        if events.is_empty() {
+            return "[]".to_string();
+        }
+        format!("[\n{}\n  ]", events.join(",\n"))
+    }
+
+    // Event times are exported as milliseconds before the destruction
+    pub fn to_json(&self) -> String {
+        let rule = self
+            .cause
+            .rule()
+            .map_or("null".to_string(), |rule| format!("\"{}\"", rule.as_str()));
+        let explorers = self
+            .explorers_present
+            .iter()
+            .map(ID::to_string)
+            .collect::<Vec<_>>();
+        let risk = self
+            .risk
+            .map_or("null".to_string(), |risk| format!("{:.2}", risk.score));
+
+        format!(
+            "{{\n  \"planet_id\": {},\n  \"cause\": \"{}\",\n  \"rule\": {},\n  \"explorers_present\": [{}],\n  \"risk_score\": {},\n  \"energy_decisions\": {},\n  \"recent_events\": {}\n}}\n",
+            self.planet_id,
+            self.cause.as_str(),
+            rule,
+            explorers.join(", "),
+            risk,
+            self.events_to_json(&self.energy_decisions),
+            self.events_to_json(&self.recent_events)
+        )
+    }
+}
+
+// Recent events of the planet, kept for the post-mortem
+#[derive(Debug, Default)]
+pub(crate) struct Journal {
+    config: PostMortemConfig,
+    events: VecDeque<TimedEvent>,
+    since_rocket: VecDeque<TimedEvent>, // Energy decisions since the last rocket was launched
+}
+
+impl Journal {
+    pub(crate) fn new(config: PostMortemConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn record(&mut self, event: PlanetEvent, now: Instant) {
+        if self.config.sink.is_none() || self.config.max_events == 0 {
+            return;
+        }
+        let timed = TimedEvent { at: now, event };
+        if event.is_energy_decision() {
+            if self.since_rocket.len() == self.config.max_events {
+                self.since_rocket.pop_front();
+            }
+            self.since_rocket.push_back(timed);
+        }
+        if self.events.len() == self.config.max_events {
+            self.events.pop_front();
+        }
+        self.events.push_back(timed);
+    }
+
+    pub(crate) fn rocket_launched(&mut self) {
+        self.since_rocket.clear();
+    }
+
+    // Finds the rule that left the planet without a rocket
+    pub(crate) fn cause(&self, running: bool) -> DefenseGap {
+        if !running {
+            return DefenseGap::PlanetStopped;
+        }
+        let last_rule = self.since_rocket.iter().rev().find_map(|timed| match timed.event {
+            PlanetEvent::Sunray { decision, .. } => Some(decision),
+            _ => None,
+        });
+        let last_spender = self.since_rocket.iter().rev().find_map(|timed| match timed.event {
+            PlanetEvent::CellSpent { explorer_id, .. } => Some(explorer_id),
+            _ => None,
+        });
+        match (last_spender, last_rule) {
+            (Some(explorer_id), rule) => DefenseGap::CellSpentByExplorer { explorer_id, rule },
+            (None, Some(rule)) => DefenseGap::RocketNotBuilt { rule },
+            (None, None) => DefenseGap::NoSunray,
+        }
+    }
+
+    pub(crate) fn post_mortem(
+        &self,
+        planet_id: u32,
+        cause: DefenseGap,
+        explorers_present: Vec<ID>,
+        risk: Option<RiskAssessment>,
+        now: Instant,
+    ) -> PostMortem {
+        PostMortem {
+            planet_id,
+            at: now,
+            cause,
+            explorers_present,
+            energy_decisions: self.since_rocket.iter().copied().collect(),
+            recent_events: self.events.iter().copied().collect(),
+            risk,
+        }
+    }
+
+    // Sends the report to the host, returns false if it couldn't be delivered
+    pub(crate) fn send(&self, post_mortem: PostMortem) -> bool {
+        self.config
+            .sink
+            .as_ref()
+            .is_some_and(|sink| sink.send(post_mortem).is_ok())
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.config.sink.is_some()
+    }
+}
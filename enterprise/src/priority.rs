@@ -0,0 +1,70 @@
+use common_game::utils::ID;
+use std::collections::HashMap;
+
+/// Priority of an explorer on the planet energy, assigned by the host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PriorityClass {
+    Low, // Scouts: served only when nobody more important wants energy, never delay a rocket
+    #[default]
+    Normal,
+    Critical, // Critical missions: the cell is kept for them
+}
+
+impl PriorityClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PriorityClass::Low => "low",
+            PriorityClass::Normal => "normal",
+            PriorityClass::Critical => "critical",
+        }
+    }
+}
+
+/// Statistics of a priority class, as seen by the host
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClassStats {
+    pub served: u64,          // Charged cells consumed by explorers of the class
+    pub refused: u64,         // Requests refused, for any reason
+    pub kept_for_higher: u64, // Requests refused to keep the cell for a higher class
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct PriorityBook {
+    classes: HashMap<ID, PriorityClass>, // Explorers not listed are Normal
+    stats: HashMap<PriorityClass, ClassStats>,
+}
+
+impl PriorityBook {
+    pub(crate) fn new(classes: HashMap<ID, PriorityClass>) -> Self {
+        Self {
+            classes,
+            stats: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn class_of(&self, explorer_id: ID) -> PriorityClass {
+        self.classes.get(&explorer_id).copied().unwrap_or_default()
+    }
+
+    pub(crate) fn set(&mut self, explorer_id: ID, class: PriorityClass) {
+        self.classes.insert(explorer_id, class);
+    }
+
+    pub(crate) fn record_served(&mut self, explorer_id: ID) {
+        let class = self.class_of(explorer_id);
+        self.stats.entry(class).or_default().served += 1;
+    }
+
+    pub(crate) fn record_refused(&mut self, explorer_id: ID, kept_for_higher: bool) {
+        let class = self.class_of(explorer_id);
+        let stats = self.stats.entry(class).or_default();
+        stats.refused += 1;
+        if kept_for_higher {
+            stats.kept_for_higher += 1;
+        }
+    }
+
+    pub(crate) fn stats(&self) -> HashMap<PriorityClass, ClassStats> {
+        self.stats.clone()
+    }
+}
@@ -0,0 +1,138 @@
+use crate::refusal::RefusalReason;
+use common_game::utils::ID;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Limits applied to every explorer
+#[derive(Debug, Clone)]
+pub struct QuotaConfig {
+    pub max_cells: Option<u32>,    // Charged cells an explorer can consume within `cell_window`
+    pub cell_window: Duration,
+    pub max_messages: Option<u32>, // Messages an explorer can send within `message_window`
+    pub message_window: Duration,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            max_cells: None,
+            cell_window: Duration::from_secs(60),
+            max_messages: None,
+            message_window: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Quota usage of an explorer, as seen by the host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaUsage {
+    pub cells_used: u32,
+    pub cells_limit: Option<u32>,
+    pub messages_sent: u32,
+    pub messages_limit: Option<u32>,
+    pub refusals: u64, // Requests refused for being over quota since the explorer first showed up
+}
+
+#[derive(Debug, Default)]
+struct ExplorerQuota {
+    cells: VecDeque<Instant>,    // When each cell inside the window was consumed
+    messages: VecDeque<Instant>, // When each message inside the window was received
+    refusals: u64,
+}
+
+impl ExplorerQuota {
+    fn prune(&mut self, now: Instant, config: &QuotaConfig) {
+        prune_window(&mut self.cells, now, config.cell_window);
+        prune_window(&mut self.messages, now, config.message_window);
+    }
+}
+
+fn prune_window(events: &mut VecDeque<Instant>, now: Instant, window: Duration) {
+    while events
+        .front()
+        .is_some_and(|at| now.saturating_duration_since(*at) >= window)
+    {
+        events.pop_front();
+    }
+}
+
+fn count(events: &VecDeque<Instant>) -> u32 {
+    u32::try_from(events.len()).unwrap_or(u32::MAX)
+}
+
+/// Per-explorer sliding windows used to enforce the quotas
+#[derive(Debug, Default)]
+pub struct QuotaTracker {
+    config: QuotaConfig,
+    explorers: HashMap<ID, ExplorerQuota>,
+}
+
+impl QuotaTracker {
+    pub fn new(config: QuotaConfig) -> Self {
+        Self {
+            config,
+            explorers: HashMap::new(),
+        }
+    }
+
+    // Counts a message against the rate limit. Messages over the limit are not counted.
+    pub fn check_message(&mut self, explorer_id: ID, now: Instant) -> Result<(), RefusalReason> {
+        let quota = self.explorers.entry(explorer_id).or_default();
+        quota.prune(now, &self.config);
+
+        if let Some(max) = self.config.max_messages
+            && count(&quota.messages) >= max
+        {
+            quota.refusals += 1;
+            return Err(RefusalReason::RateLimited);
+        }
+        quota.messages.push_back(now);
+        Ok(())
+    }
+
+    // Checks that the explorer can consume one more charged cell
+    pub fn check_cell(&mut self, explorer_id: ID, now: Instant) -> Result<(), RefusalReason> {
+        let quota = self.explorers.entry(explorer_id).or_default();
+        quota.prune(now, &self.config);
+
+        if let Some(max) = self.config.max_cells
+            && count(&quota.cells) >= max
+        {
+            quota.refusals += 1;
+            return Err(RefusalReason::CellQuotaExceeded);
+        }
+        Ok(())
+    }
+
+    // Records a charged cell consumed on behalf of the explorer
+    pub fn record_cell(&mut self, explorer_id: ID, now: Instant) {
+        self.explorers
+            .entry(explorer_id)
+            .or_default()
+            .cells
+            .push_back(now);
+    }
+
+    pub fn usage(&self, explorer_id: ID, now: Instant) -> Option<QuotaUsage> {
+        let quota = self.explorers.get(&explorer_id)?;
+        let in_window = |events: &VecDeque<Instant>, window: Duration| {
+            let recent = events
+                .iter()
+                .filter(|at| now.saturating_duration_since(**at) < window)
+                .count();
+            u32::try_from(recent).unwrap_or(u32::MAX)
+        };
+
+        Some(QuotaUsage {
+            cells_used: in_window(&quota.cells, self.config.cell_window),
+            cells_limit: self.config.max_cells,
+            messages_sent: in_window(&quota.messages, self.config.message_window),
+            messages_limit: self.config.max_messages,
+            refusals: quota.refusals,
+        })
+    }
+
+    pub fn explorers(&self) -> impl Iterator<Item = &ID> {
+        self.explorers.keys()
+    }
+}
@@ -0,0 +1,56 @@
+use crate::recipes::{inputs_match, Recipes};
+use common_game::components::resource::{BasicResourceType, ComplexResourceType, ResourceType};
+
+/// Request evaluated by the quote API, without sending or consuming anything
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteRequest {
+    Generate(BasicResourceType),
+    Combine {
+        resource: ComplexResourceType,
+        inputs: (ResourceType, ResourceType), // Types of the resources the explorer would send
+    },
+}
+
+/// How the planet would answer a request if it was sent now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quote {
+    pub supported: bool,      // The planet has the generation or combination recipe
+    pub cell_available: bool, // A charged cell is available right now
+    pub inputs_valid: bool,   // The inputs match the recipe, always true for generations
+    pub from_stockpile: bool, // The resource would be handed out from the stockpile
+    pub energy_cost: u32,     // Charged cells consumed if the request succeeds
+}
+
+impl Quote {
+    pub fn would_succeed(&self) -> bool {
+        self.supported && (self.cell_available || self.from_stockpile) && self.inputs_valid
+    }
+}
+
+pub(crate) fn quote(
+    request: QuoteRequest,
+    recipes: &Recipes,
+    cell_available: bool,
+    stockpiled_carbon: usize,
+) -> Quote {
+    let (supported, inputs_valid) = match request {
+        QuoteRequest::Generate(resource) => (recipes.generation.contains(&resource), true),
+        QuoteRequest::Combine { resource, inputs } => (
+            recipes.combination.contains(&resource),
+            inputs_match(resource, inputs),
+        ),
+    };
+
+    let from_stockpile = supported
+        && stockpiled_carbon > 0
+        && matches!(request, QuoteRequest::Generate(BasicResourceType::Carbon));
+
+    Quote {
+        supported,
+        cell_available,
+        inputs_valid,
+        from_stockpile,
+        // Every generation and combination takes one charged cell, unless served from the stockpile
+        energy_cost: u32::from(supported && !from_stockpile),
+    }
+}
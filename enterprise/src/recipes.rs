@@ -0,0 +1,90 @@
+use common_game::components::resource::{
+    BasicResourceType, Combinator, ComplexResourceType, Generator, ResourceType,
+};
+use std::collections::HashSet;
+
+// Resources Enterprise can generate
+pub(crate) fn generation_rules() -> Vec<BasicResourceType> {
+    vec![BasicResourceType::Carbon]
+}
+
+// Complex resources Enterprise can combine, C-type planets support all of them
+pub(crate) fn combination_rules() -> Vec<ComplexResourceType> {
+    vec![
+        ComplexResourceType::Water,
+        ComplexResourceType::Diamond,
+        ComplexResourceType::Life,
+        ComplexResourceType::Robot,
+        ComplexResourceType::Dolphin,
+        ComplexResourceType::AIPartner,
+    ]
+}
+
+// Name of a resource in the reports and exports, e.g. "Carbon"
+pub(crate) fn resource_name(resource: ResourceType) -> String {
+    match resource {
+        ResourceType::Basic(basic) => format!("{:?}", basic),
+        ResourceType::Complex(complex) => format!("{:?}", complex),
+    }
+}
+
+/// Input resources of the recipe of a complex resource
+pub fn recipe_inputs(resource: ComplexResourceType) -> (ResourceType, ResourceType) {
+    match resource {
+        ComplexResourceType::Water => (
+            ResourceType::Basic(BasicResourceType::Hydrogen),
+            ResourceType::Basic(BasicResourceType::Oxygen),
+        ),
+        ComplexResourceType::Diamond => (
+            ResourceType::Basic(BasicResourceType::Carbon),
+            ResourceType::Basic(BasicResourceType::Carbon),
+        ),
+        ComplexResourceType::Life => (
+            ResourceType::Complex(ComplexResourceType::Water),
+            ResourceType::Basic(BasicResourceType::Carbon),
+        ),
+        ComplexResourceType::Robot => (
+            ResourceType::Basic(BasicResourceType::Silicon),
+            ResourceType::Complex(ComplexResourceType::Life),
+        ),
+        ComplexResourceType::Dolphin => (
+            ResourceType::Complex(ComplexResourceType::Water),
+            ResourceType::Complex(ComplexResourceType::Life),
+        ),
+        ComplexResourceType::AIPartner => (
+            ResourceType::Complex(ComplexResourceType::Robot),
+            ResourceType::Complex(ComplexResourceType::Diamond),
+        ),
+    }
+}
+
+/// Returns true if the inputs match the recipe of the resource, in any order
+pub fn inputs_match(resource: ComplexResourceType, inputs: (ResourceType, ResourceType)) -> bool {
+    let (a, b) = recipe_inputs(resource);
+    inputs == (a, b) || inputs == (b, a)
+}
+
+/// Generation and combination recipes enabled on the planet
+#[derive(Debug, Clone, Default)]
+pub struct Recipes {
+    pub generation: HashSet<BasicResourceType>,
+    pub combination: HashSet<ComplexResourceType>,
+}
+
+impl Recipes {
+    // The recipes Enterprise is created with
+    pub fn enterprise() -> Self {
+        Self {
+            generation: generation_rules().into_iter().collect(),
+            combination: combination_rules().into_iter().collect(),
+        }
+    }
+
+    // The recipes actually enabled on a running planet
+    pub fn of(generator: &Generator, combinator: &Combinator) -> Self {
+        Self {
+            generation: generator.all_available_recipes(),
+            combination: combinator.all_available_recipes(),
+        }
+    }
+}
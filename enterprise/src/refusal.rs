@@ -0,0 +1,45 @@
+/// Reasons for which the planet refuses to serve an explorer request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RefusalReason {
+    RateLimited,           // The explorer sent too many messages within the rate window
+    CellQuotaExceeded,     // The explorer consumed too many charged cells within the quota window
+    Blacklisted,           // The explorer is blacklisted for misbehaving
+    AuctionInProgress,     // The next charged cell is being auctioned
+    CellReserved,          // The next charged cell was awarded to another explorer
+    KeptForHigherPriority, // The charged cell is kept for an explorer of a higher priority class
+    BudgetExceeded,        // The explorer can't afford the resource
+    KeptForDefense,        // The last charged cell is kept for an emergency rocket
+}
+
+impl RefusalReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RefusalReason::RateLimited => "rate_limited",
+            RefusalReason::CellQuotaExceeded => "cell_quota_exceeded",
+            RefusalReason::Blacklisted => "blacklisted",
+            RefusalReason::AuctionInProgress => "auction_in_progress",
+            RefusalReason::CellReserved => "cell_reserved",
+            RefusalReason::KeptForHigherPriority => "kept_for_higher_priority",
+            RefusalReason::BudgetExceeded => "budget_exceeded",
+            RefusalReason::KeptForDefense => "kept_for_defense",
+        }
+    }
+
+    // Message sent back to the explorer when the response can carry one
+    pub fn message(self) -> String {
+        match self {
+            RefusalReason::RateLimited => "Too many messages, slow down".to_string(),
+            RefusalReason::CellQuotaExceeded => "Energy cell quota exceeded".to_string(),
+            RefusalReason::Blacklisted => "Explorer blacklisted by the planet".to_string(),
+            RefusalReason::AuctionInProgress => "The next energy cell is being auctioned".to_string(),
+            RefusalReason::CellReserved => "The next energy cell is reserved".to_string(),
+            RefusalReason::KeptForHigherPriority => {
+                "The energy cell is kept for a higher priority explorer".to_string()
+            }
+            RefusalReason::BudgetExceeded => "Not enough credits for this resource".to_string(),
+            RefusalReason::KeptForDefense => {
+                "The energy cell is kept to defend the planet from asteroids".to_string()
+            }
+        }
+    }
+}
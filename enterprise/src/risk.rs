@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// Asteroids kept to estimate their frequency, the oldest are dropped first
+const MAX_ASTEROIDS: usize = 64;
+
+/// Weight of each factor in the risk score, they add up to 1 by default
+#[derive(Debug, Clone)]
+pub struct RiskWeights {
+    pub asteroid_frequency: f64,
+    pub recent_asteroid: f64,
+    pub no_rocket: f64,
+    pub no_charged_cell: f64,
+    pub explorers_present: f64, // Explorers may spend the cell an emergency rocket would need
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            asteroid_frequency: 0.3,
+            recent_asteroid: 0.2,
+            no_rocket: 0.3,
+            no_charged_cell: 0.1,
+            explorers_present: 0.1,
+        }
+    }
+}
+
+/// How the risk of being hit without defense is scored
+#[derive(Debug, Clone)]
+pub struct RiskConfig {
+    pub window: Duration,        // Asteroids within it make the frequency, the last one fades over it
+    pub frequent_asteroids: u32, // Asteroids within the window that make the frequency factor full
+    pub weights: RiskWeights,
+    pub elevated: f64, // Scores from here on are elevated
+    pub high: f64,     // Scores from here on are high
+    // With a high score and no rocket, the sunray goes to a rocket instead of the explorers
+    pub defend_when_high: bool,
+    // With a high score and no rocket, explorers can't spend the last charged cell
+    pub keep_cell_for_defense: bool,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(60),
+            frequent_asteroids: 3,
+            weights: RiskWeights::default(),
+            elevated: 0.4,
+            high: 0.7,
+            defend_when_high: false,
+            keep_cell_for_defense: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskLevel {
+    Low,
+    Elevated,
+    High,
+}
+
+impl RiskLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Elevated => "elevated",
+            RiskLevel::High => "high",
+        }
+    }
+}
+
+/// Factors of the risk score, each from 0 to 1
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RiskFactors {
+    pub asteroid_frequency: f64,
+    pub recent_asteroid: f64,
+    pub no_rocket: f64,
+    pub no_charged_cell: f64,
+    pub explorers_present: f64,
+}
+
+/// Risk of being hit by an asteroid without defense, as seen by the host
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskAssessment {
+    pub score: f64, // From 0 to 1
+    pub level: RiskLevel,
+    pub factors: RiskFactors,
+    pub at: Instant,
+}
+
+// What the planet knows about the threat, and the last assessment made with it
+#[derive(Debug, Default)]
+pub(crate) struct RiskModel {
+    config: RiskConfig,
+    asteroids: VecDeque<Instant>,
+    last: Option<RiskAssessment>,
+}
+
+impl RiskModel {
+    pub(crate) fn new(config: RiskConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn record_asteroid(&mut self, now: Instant) {
+        if self.asteroids.len() == MAX_ASTEROIDS {
+            self.asteroids.pop_front();
+        }
+        self.asteroids.push_back(now);
+    }
+
+    pub(crate) fn assess(
+        &mut self,
+        has_rocket: bool,
+        charged_cell: bool,
+        explorers_present: bool,
+        now: Instant,
+    ) -> RiskAssessment {
+        let window = self.config.window.as_secs_f64().max(f64::EPSILON);
+        let recent = self
+            .asteroids
+            .iter()
+            .filter(|at| now.saturating_duration_since(**at).as_secs_f64() <= window)
+            .count();
+        let factors = RiskFactors {
+            asteroid_frequency: (recent as f64 / self.config.frequent_asteroids.max(1) as f64).min(1.0),
+            // Fades linearly to nothing over the window
+            recent_asteroid: self.asteroids.back().map_or(0.0, |last| {
+                (1.0 - now.saturating_duration_since(*last).as_secs_f64() / window).max(0.0)
+            }),
+            no_rocket: if has_rocket { 0.0 } else { 1.0 },
+            no_charged_cell: if charged_cell { 0.0 } else { 1.0 },
+            explorers_present: if explorers_present { 1.0 } else { 0.0 },
+        };
+
+        let weights = &self.config.weights;
+        let score = (weights.asteroid_frequency * factors.asteroid_frequency
+            + weights.recent_asteroid * factors.recent_asteroid
+            + weights.no_rocket * factors.no_rocket
+            + weights.no_charged_cell * factors.no_charged_cell
+            + weights.explorers_present * factors.explorers_present)
+            .clamp(0.0, 1.0);
+        let level = if score >= self.config.high {
+            RiskLevel::High
+        } else if score >= self.config.elevated {
+            RiskLevel::Elevated
+        } else {
+            RiskLevel::Low
+        };
+
+        let assessment = RiskAssessment {
+            score,
+            level,
+            factors,
+            at: now,
+        };
+        self.last = Some(assessment);
+        assessment
+    }
+
+    pub(crate) fn last(&self) -> Option<RiskAssessment> {
+        self.last
+    }
+
+    pub(crate) fn defends_when_high(&self) -> bool {
+        self.config.defend_when_high
+    }
+}
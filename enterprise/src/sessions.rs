@@ -0,0 +1,123 @@
+use common_game::utils::ID;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Bookkeeping for a single explorer currently on the planet
+#[derive(Debug, Clone)]
+pub struct ExplorerSession {
+    pub arrived_at: Instant,   // When the orchestrator announced the explorer
+    pub last_activity: Instant, // Last arrival or message received from the explorer
+    pub last_demand: Option<Instant>, // Last generate/combine request, the ones that need energy
+    pub pending_demand: Option<Instant>, // Oldest energy request not served yet
+}
+
+/// Sessions of the explorers on the planet, used to forget explorers that crashed
+/// without the orchestrator ever sending a departure
+#[derive(Debug, Default)]
+pub struct ExplorerSessions {
+    active: HashMap<ID, ExplorerSession>,
+    evicted: HashSet<ID>, // Explorers removed for inactivity that never departed
+}
+
+impl ExplorerSessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Opens (or reopens) the session of an explorer that just landed
+    pub fn arrive(&mut self, explorer_id: ID, now: Instant) {
+        self.evicted.remove(&explorer_id);
+        self.active.insert(
+            explorer_id,
+            ExplorerSession {
+                arrived_at: now,
+                last_activity: now,
+                last_demand: None,
+                pending_demand: None,
+            },
+        );
+    }
+
+    // Closes the session of a departing explorer, returning it if it was still active
+    pub fn depart(&mut self, explorer_id: ID) -> Option<ExplorerSession> {
+        self.evicted.remove(&explorer_id);
+        self.active.remove(&explorer_id)
+    }
+
+    // Records activity for an explorer.
+    // An explorer that was evicted but is still talking to us is revived.
+    // Returns false if the explorer never arrived on the planet.
+    pub fn touch(&mut self, explorer_id: ID, now: Instant) -> bool {
+        if let Some(session) = self.active.get_mut(&explorer_id) {
+            session.last_activity = now;
+            true
+        } else if self.evicted.remove(&explorer_id) {
+            self.arrive(explorer_id, now);
+            true
+        } else {
+            false
+        }
+    }
+
+    // Records that an explorer asked for something that consumes energy
+    pub fn record_demand(&mut self, explorer_id: ID, now: Instant) {
+        if let Some(session) = self.active.get_mut(&explorer_id) {
+            session.last_demand = Some(now);
+            session.pending_demand.get_or_insert(now);
+        }
+    }
+
+    // Records that an explorer got the resource it asked for
+    pub fn record_served(&mut self, explorer_id: ID) {
+        if let Some(session) = self.active.get_mut(&explorer_id) {
+            session.pending_demand = None;
+        }
+    }
+
+    // Returns true if any explorer asked for energy during the last `window`
+    pub fn has_recent_demand(&self, now: Instant, window: Duration) -> bool {
+        self.active.values().any(|session| {
+            session
+                .last_demand
+                .is_some_and(|at| now.saturating_duration_since(at) <= window)
+        })
+    }
+
+    // Removes every session idle for longer than `timeout`, returning the evicted IDs
+    pub fn evict_expired(&mut self, now: Instant, timeout: Duration) -> Vec<ID> {
+        let expired: Vec<ID> = self
+            .active
+            .iter()
+            .filter(|(_, session)| now.saturating_duration_since(session.last_activity) > timeout)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in &expired {
+            self.active.remove(id);
+            self.evicted.insert(*id);
+        }
+        expired
+    }
+
+    // Forgets every explorer without treating them as strangers:
+    // they are considered evicted and come back as soon as they talk again
+    pub fn suspend_all(&mut self) {
+        self.evicted.extend(self.active.drain().map(|(id, _)| id));
+    }
+
+    pub fn was_evicted(&self, explorer_id: ID) -> bool {
+        self.evicted.contains(&explorer_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ID, &ExplorerSession)> {
+        self.active.iter()
+    }
+}
@@ -0,0 +1,26 @@
+use common_game::components::resource::Carbon;
+use std::collections::VecDeque;
+
+// Carbon produced with the energy the planet would otherwise waste
+#[derive(Debug, Default)]
+pub(crate) struct CarbonStockpile {
+    carbon: VecDeque<Carbon>,
+}
+
+impl CarbonStockpile {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, carbon: Carbon) {
+        self.carbon.push_back(carbon);
+    }
+
+    pub(crate) fn take(&mut self) -> Option<Carbon> {
+        self.carbon.pop_front()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.carbon.len()
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// Arrivals kept to estimate the sunray interval, the oldest are dropped first
+const MAX_ARRIVALS: usize = 64;
+// Intervals needed before the model is half confident
+const CONFIDENCE_SAMPLES: f64 = 8.0;
+// Minimum correlation between the intervals of two cycles for the arrivals to be considered periodic
+const MIN_PERIOD_CORRELATION: f64 = 0.5;
+
+/// How the sunray model is used by the energy policy
+#[derive(Debug, Clone)]
+pub struct SunrayModelConfig {
+    // A gap before the next sunray longer than this many mean intervals is a night
+    pub night_factor: f64,
+    // Minimum confidence of the model for the planet to build a rocket instead of keeping the
+    // cell for the explorers when a night is ahead. `None` keeps the cell for them anyway.
+    pub defend_at_night: Option<f64>,
+}
+
+impl Default for SunrayModelConfig {
+    fn default() -> Self {
+        Self {
+            night_factor: 2.0,
+            defend_at_night: None,
+        }
+    }
+}
+
+/// Statistical model of the sunray arrivals, as seen by the host
+#[derive(Debug, Clone, PartialEq)]
+pub struct SunrayPattern {
+    pub samples: usize,          // Intervals between sunrays observed
+    pub rate_per_minute: f64,    // Sunrays per minute
+    pub mean_interval: Duration,
+    pub burstiness: f64,         // Coefficient of variation of the intervals: 0 regular, 1 random, more in bursts
+    pub period: Option<usize>,   // Sunrays in a cycle (e.g. a day and a night), if the arrivals repeat
+    pub cycle: Option<Duration>, // Length of a cycle
+    pub next_interval: Duration, // Expected time between the last sunray and the next one
+    pub confidence: f64,         // From 0 to 1, grows with the samples and how predictable they are
+}
+
+impl SunrayPattern {
+    // The next sunray is expected after an unusually long gap
+    pub fn night_ahead(&self, night_factor: f64) -> bool {
+        self.next_interval.as_secs_f64() > self.mean_interval.as_secs_f64() * night_factor
+    }
+}
+
+// Observed sunray arrivals, used to estimate when the next cell will be charged
+#[derive(Debug, Default)]
+pub(crate) struct SunrayHistory {
+    arrivals: VecDeque<Instant>,
+}
+
+// Number of intervals in a cycle of the series and how strongly the cycles match, if any
+fn detect_period(intervals: &[f64], mean: f64) -> Option<(usize, f64)> {
+    let variance = intervals.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+    if variance <= f64::EPSILON {
+        return None; // Perfectly regular arrivals have no cycle
+    }
+    (2..=intervals.len() / 2)
+        .map(|lag| {
+            let covariance = intervals
+                .iter()
+                .zip(&intervals[lag..])
+                .map(|(a, b)| (a - mean) * (b - mean))
+                .sum::<f64>();
+            (lag, covariance / variance)
+        })
+        .filter(|(_, correlation)| *correlation >= MIN_PERIOD_CORRELATION)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+impl SunrayHistory {
+    pub(crate) fn record(&mut self, now: Instant) {
+        if self.arrivals.len() == MAX_ARRIVALS {
+            self.arrivals.pop_front();
+        }
+        self.arrivals.push_back(now);
+    }
+
+    fn intervals(&self) -> Vec<f64> {
+        self.arrivals
+            .iter()
+            .zip(self.arrivals.iter().skip(1))
+            .map(|(a, b)| b.saturating_duration_since(*a).as_secs_f64())
+            .collect()
+    }
+
+    // Mean time between two sunrays, `None` until two sunrays were observed
+    pub(crate) fn mean_interval(&self) -> Option<Duration> {
+        let first = self.arrivals.front()?;
+        let last = self.arrivals.back()?;
+        let intervals = self.arrivals.len().checked_sub(1).filter(|n| *n > 0)?;
+        Some(last.saturating_duration_since(*first) / intervals as u32)
+    }
+
+    // Rate, burstiness and cycles of the arrivals, `None` until two intervals were observed
+    pub(crate) fn pattern(&self) -> Option<SunrayPattern> {
+        let intervals = self.intervals();
+        if intervals.len() < 2 {
+            return None;
+        }
+        let samples = intervals.len();
+        let mean = intervals.iter().sum::<f64>() / samples as f64;
+        if mean <= 0.0 {
+            return None;
+        }
+        let deviation =
+            (intervals.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples as f64).sqrt();
+        let burstiness = deviation / mean;
+
+        // A cycle predicts the next interval from the same point of the previous cycle
+        let period = detect_period(&intervals, mean);
+        let (next_interval, predictability) = match period {
+            Some((lag, correlation)) => (intervals[samples - lag], correlation),
+            None => (mean, 1.0 / (1.0 + burstiness)),
+        };
+        let confidence = samples as f64 / (samples as f64 + CONFIDENCE_SAMPLES) * predictability;
+
+        Some(SunrayPattern {
+            samples,
+            rate_per_minute: 60.0 / mean,
+            mean_interval: Duration::from_secs_f64(mean),
+            burstiness,
+            period: period.map(|(lag, _)| lag),
+            cycle: period.map(|(lag, _)| Duration::from_secs_f64(intervals[samples - lag..].iter().sum())),
+            next_interval: Duration::from_secs_f64(next_interval),
+            confidence: confidence.clamp(0.0, 1.0),
+        })
+    }
+
+    // When the next sunray is expected. An overdue sunray is expected at the following interval.
+    pub(crate) fn next_arrival(&self, now: Instant) -> Option<Instant> {
+        let interval = self.mean_interval().filter(|interval| !interval.is_zero())?;
+        let next_interval = self
+            .pattern()
+            .map_or(interval, |pattern| pattern.next_interval);
+        let expected = *self.arrivals.back()? + next_interval;
+        if expected >= now {
+            return Some(expected);
+        }
+        let skipped = (now.duration_since(expected).as_secs_f64() / interval.as_secs_f64()).ceil();
+        Some(expected + interval.mul_f64(skipped))
+    }
+
+    // Time left before a charged cell is available
+    pub(crate) fn energy_eta(&self, charged_cell: bool, now: Instant) -> Option<Duration> {
+        if charged_cell {
+            return Some(Duration::ZERO);
+        }
+        self.next_arrival(now).map(|at| at.saturating_duration_since(now))
+    }
+}
@@ -1,5 +1,33 @@
 use crate::create_planet;
+use crate::create_planet_with_config;
 use crate::EnterpriseAi;
+use crate::EnterpriseConfig;
+use crate::DemandKind;
+use crate::DemandModel;
+use crate::QuotaConfig;
+use crate::ExplorerSessions;
+use crate::{Ban, ConductConfig, Offence};
+use crate::UnsupportedDemandReport;
+use crate::GalaxyDirectory;
+use crate::QuoteRequest;
+use crate::CombineRejection;
+use crate::AssistConfig;
+use crate::assist::combination_request;
+use crate::{CraftPlanner, CraftStep, PlanError};
+use crate::RecipeGraph;
+use crate::CacheConfig;
+use crate::AuctionConfig;
+use crate::PriorityClass;
+use crate::{TransactionKind, TransactionOutcome};
+use crate::{BillItem, BillingConfig, RefusalReason};
+use crate::{DeferredDelivery, DeferredOutcome, WaitlistConfig};
+use crate::sunrays::SunrayHistory;
+use crate::adaptive::PolicyLearner;
+use crate::{AdaptiveConfig, EnergyPolicy};
+use crate::risk::RiskModel;
+use crate::{RiskConfig, RiskLevel};
+use crate::{DefenseGap, ExplorerDemand, PlanetEvent, PostMortem, PostMortemConfig};
+use crate::postmortem::Journal;
 
 #[cfg(test)]
 mod tests {
@@ -16,7 +44,8 @@ mod tests {
     use common_game::protocols::planet_explorer::*;
     use crossbeam_channel::{Receiver, Sender, unbounded};
     use std::thread;
-    use std::time::Duration;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
 
     /// Test that our planet can be created and follows Type C constraints
     #[test]
@@ -949,4 +978,2482 @@ mod tests {
             _ => assert!(false),
         }
     }
-}
\ No newline at end of file
+
+    //Test that an explorer that never departs is evicted after the idle timeout
+    #[test]
+    fn test_stale_explorer_eviction() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (_tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, _rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            explorer_timeout: Some(Duration::from_millis(20)),
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, _ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer that will crash without departing
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Let the explorer session expire
+        thread::sleep(Duration::from_millis(40));
+
+        //Send sunray
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Check internal state
+        tx_orch_in.send(OrchestratorToPlanet::InternalStateRequest).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::InternalStateResponse { planet_id:67, planet_state:dummy_state }) => {
+                assert!(dummy_state.has_rocket); //The stale explorer was evicted, so the planet went back to defense mode
+                assert_eq!(dummy_state.charged_cells_count, 0); //The energy cell was used to build the rocket
+            }
+            _ => assert!(false),
+        }
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    //Test the session bookkeeping (eviction and revival)
+    #[test]
+    fn test_explorer_sessions() {
+        let mut sessions = ExplorerSessions::new();
+        let start = Instant::now();
+
+        sessions.arrive(1, start);
+        sessions.arrive(2, start);
+        assert!(sessions.touch(2, start + Duration::from_secs(8)));
+        assert!(!sessions.touch(3, start)); //Explorer 3 never arrived
+
+        let evicted = sessions.evict_expired(start + Duration::from_secs(10), Duration::from_secs(5));
+        assert_eq!(evicted, vec![1]);
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions.was_evicted(1));
+
+        //An evicted explorer that talks again is revived
+        assert!(sessions.touch(1, start + Duration::from_secs(11)));
+        assert!(!sessions.was_evicted(1));
+        assert_eq!(sessions.len(), 2);
+    }
+
+    //Test that idle explorers don't stop the planet from building a rocket
+    #[test]
+    fn test_sunray_with_idle_explorer() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            demand_window: Some(Duration::from_secs(5)),
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, _ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Send sunray
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Check internal state
+        tx_orch_in.send(OrchestratorToPlanet::InternalStateRequest).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::InternalStateResponse { planet_id:67, planet_state:dummy_state }) => {
+                assert!(dummy_state.has_rocket); //The explorer never asked for energy, so the planet built a rocket
+                assert_eq!(dummy_state.charged_cells_count, 0); //The energy cell was used to build the rocket
+            }
+            _ => assert!(false),
+        }
+
+        //Send asteroid
+        tx_orch_in.send(OrchestratorToPlanet::Asteroid(Asteroid::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::AsteroidAck { planet_id:67, rocket:r }) => {
+                assert!(r.is_some()); //The rocket built for the idle explorer saves the planet
+            }
+            _ => assert!(false),
+        }
+
+        //Generate carbon request
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none()) //It should be none because there is no energy cell
+            }
+            _ => assert!(false),
+        }
+
+        //Send 2nd sunray
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Check internal state (again)
+        tx_orch_in.send(OrchestratorToPlanet::InternalStateRequest).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::InternalStateResponse { planet_id:67, planet_state:dummy_state }) => {
+                assert!(!dummy_state.has_rocket); //The explorer asked for energy, so no rocket is built
+                assert_eq!(dummy_state.charged_cells_count, 1); //The energy cell is kept for the explorer
+            }
+            _ => assert!(false),
+        }
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    //Test the demand model estimates
+    #[test]
+    fn test_demand_model_forecast() {
+        let mut model = DemandModel::new();
+        let start = Instant::now();
+
+        //Explorer 1 asks for energy every second
+        for i in 0..5 {
+            model.record(1, DemandKind::Generate, start + Duration::from_secs(i));
+        }
+        model.record(1, DemandKind::EnergyQuery, start + Duration::from_secs(5));
+        model.record(2, DemandKind::EnergyQuery, start + Duration::from_secs(5));
+
+        let stats = model.explorer(1).unwrap();
+        assert_eq!(stats.energy_requests(), 5);
+        assert_eq!(stats.energy_queries, 1);
+        assert_eq!(model.global.energy_queries, 2);
+
+        //Right after the last request we expect about one request per second
+        let forecast = model.forecast(1, start + Duration::from_secs(4), Duration::from_secs(10));
+        assert!((forecast.rate_per_sec - 1.0).abs() < 0.01);
+        assert!(forecast.probability > 0.99);
+
+        //After a long silence the predicted demand drops
+        let later = model.forecast(1, start + Duration::from_secs(100), Duration::from_secs(10));
+        assert!(later.probability < forecast.probability);
+        assert!(later.rate_per_sec < 0.02);
+
+        //Explorer 2 only asked how much energy there is, so it has no predicted demand
+        assert_eq!(model.forecast(2, start + Duration::from_secs(5), Duration::from_secs(10)).expected_requests, 0.0);
+    }
+
+    //Test that the demand model can be inspected through the handle while the planet runs
+    #[test]
+    fn test_demand_model_through_handle() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, EnterpriseConfig::default()); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Explorer requests
+        tx_expl_in.send(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 }).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: energy }) => {
+                assert_eq!(energy, 0) //There should be no energy cell
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none()) //It should be none because there is no energy cell
+            }
+            _ => assert!(false),
+        }
+
+        //Inspect the model
+        let model = ai_handle.demand_model();
+        let stats = model.explorer(1).unwrap();
+        assert_eq!(stats.energy_queries, 1);
+        assert_eq!(stats.generate_requests, 1);
+        assert_eq!(model.global.energy_requests(), 1);
+        assert!(ai_handle.predicted_demand(1, Duration::from_secs(30)).probability > 0.0);
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    //Test the per-explorer quotas
+    #[test]
+    fn test_explorer_quotas() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            quota: QuotaConfig {
+                max_cells: Some(1),
+                cell_window: Duration::from_secs(60),
+                max_messages: Some(3),
+                message_window: Duration::from_secs(60),
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Two sunrays and two carbon requests, only the first one is within the quota
+        for expect_carbon in [true, false] {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                    assert_eq!(r.is_some(), expect_carbon) //The second request is over the cell quota
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //Check internal state
+        tx_orch_in.send(OrchestratorToPlanet::InternalStateRequest).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::InternalStateResponse { planet_id:67, planet_state:dummy_state }) => {
+                assert_eq!(dummy_state.charged_cells_count, 1); //The refused request didn't consume the energy cell
+            }
+            _ => assert!(false),
+        }
+
+        //Two more messages: the first reaches the message limit, the second gets an empty answer
+        tx_expl_in.send(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 }).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: energy }) => {
+                assert_eq!(energy, 1)
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 }).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: energy }) => {
+                assert_eq!(energy, 0) //The charged cell isn't disclosed to an explorer over quota
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 }).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::SupportedResourceResponse { resource_list }) => {
+                assert!(resource_list.is_empty())
+            }
+            _ => assert!(false),
+        }
+
+        //Check the usage through the handle
+        let usage = ai_handle.quota_usage(1).unwrap();
+        assert_eq!(usage.cells_used, 1);
+        assert_eq!(usage.cells_limit, Some(1));
+        assert_eq!(usage.messages_sent, 3);
+        assert_eq!(usage.refusals, 3);
+        assert!(ai_handle.quota_usage(2).is_none());
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_explorer_blacklisting() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            conduct: ConductConfig {
+                ban_threshold: Some(4),
+                ..ConductConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Sunray
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Two hydrogen requests, the planet can't generate hydrogen
+        for _ in 0..2 {
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Hydrogen,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                    assert!(r.is_none())
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //The explorer is now blacklisted, even a valid request is refused
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+
+        let conduct = ai_handle.conduct(1).unwrap();
+        assert_eq!(conduct.offences.get(&Offence::InvalidRequest), Some(&2));
+        assert_eq!(conduct.bans, 1);
+        assert!(matches!(ai_handle.blacklist().get(&1), Some(Ban::Temporary { .. })));
+
+        //Once pardoned the explorer is served again
+        assert!(ai_handle.pardon(1));
+        assert!(!ai_handle.pardon(1));
+        assert!(ai_handle.blacklist().is_empty());
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_banned_explorer_evicted() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            explorer_timeout: Some(Duration::from_millis(100)),
+            conduct: ConductConfig {
+                ban_threshold: Some(4),
+                ..ConductConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Two hydrogen requests get the explorer blacklisted
+        for _ in 0..2 {
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Hydrogen,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                    assert!(r.is_none())
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //The banned explorer keeps asking for energy, it is neither active nor demanding
+        for _ in 0..4 {
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                    assert!(r.is_none())
+                }
+                _ => assert!(false),
+            }
+            thread::sleep(Duration::from_millis(40));
+        }
+        assert_eq!(ai_handle.demand_model().explorer(1).map(|stats| stats.generate_requests), Some(1));
+
+        //It got evicted, so the sunray goes to a rocket
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_orch_in.send(OrchestratorToPlanet::InternalStateRequest).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::InternalStateResponse { planet_id:67, planet_state:dummy_state }) => {
+                assert!(dummy_state.has_rocket);
+            }
+            _ => assert!(false),
+        }
+
+        // Stop
+        tx_orch_in.send(OrchestratorToPlanet::StopPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StopPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //A stopped planet doesn't record the demand nor the offences
+        assert!(ai_handle.pardon(1));
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Hydrogen,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::Stopped) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        assert_eq!(ai_handle.demand_model().explorer(1).map(|stats| stats.generate_requests), Some(1));
+        assert_eq!(ai_handle.conduct(1).unwrap().offences.get(&Offence::InvalidRequest), Some(&2));
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_demand_report() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, EnterpriseConfig::default()); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Requests for resources Enterprise can't generate
+        for resource in [BasicResourceType::Hydrogen, BasicResourceType::Hydrogen, BasicResourceType::Oxygen, BasicResourceType::Carbon] {
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                    assert!(r.is_none()) //No charged cell, nothing is generated
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //Only the unsupported resources end up in the report
+        let report = ai_handle.unsupported_demand();
+        assert_eq!(report.total(), 3);
+        let hydrogen = report.get(ResourceType::Basic(BasicResourceType::Hydrogen)).unwrap();
+        assert_eq!(hydrogen.requests, 2);
+        assert_eq!(hydrogen.by_explorer.get(&1), Some(&2));
+        assert!(report.get(ResourceType::Basic(BasicResourceType::Carbon)).is_none());
+        assert_eq!(report.most_requested()[0], (ResourceType::Basic(BasicResourceType::Hydrogen), 2));
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Combination misses are aggregated the same way, per explorer
+        let mut report = UnsupportedDemandReport::new();
+        let now = Instant::now();
+        report.record(ResourceType::Complex(ComplexResourceType::Dolphin), 1, now);
+        report.record(ResourceType::Complex(ComplexResourceType::Dolphin), 2, now);
+        let dolphin = report.get(ResourceType::Complex(ComplexResourceType::Dolphin)).unwrap();
+        assert_eq!(dolphin.requests, 2);
+        assert_eq!(dolphin.by_explorer.len(), 2);
+        assert_eq!(dolphin.first_request, Some(now));
+    }
+
+    #[test]
+    fn test_galaxy_directory_referrals() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let hydrogen = ResourceType::Basic(BasicResourceType::Hydrogen);
+        let oxygen = ResourceType::Basic(BasicResourceType::Oxygen);
+        let mut directory = GalaxyDirectory::new();
+        directory.register(hydrogen, 5);
+        directory.register(hydrogen, 6);
+        let config = EnterpriseConfig {
+            directory,
+            quota: QuotaConfig {
+                max_cells: Some(0), //Referrals use no cell, so the explorer is referred anyway
+                ..QuotaConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Hydrogen request, the explorer is referred to the preferred provider
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Hydrogen,}).unwrap();
+        assert!(rx_expl_out.recv_timeout(Duration::from_millis(100)).is_ok());
+        let referral = ai_handle.referral(1).unwrap();
+        assert_eq!(referral.resource, hydrogen);
+        assert_eq!(referral.planet_id, Some(5));
+
+        //Oxygen request, nobody provides it yet
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Oxygen,}).unwrap();
+        assert!(rx_expl_out.recv_timeout(Duration::from_millis(100)).is_ok());
+        assert_eq!(ai_handle.referral(1).unwrap().planet_id, None);
+
+        //The host updates the directory while the planet is running
+        ai_handle.register_provider(oxygen, 7);
+        assert!(ai_handle.unregister_provider(hydrogen, 5));
+        assert_eq!(ai_handle.best_next_hop(hydrogen), Some(6));
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Oxygen,}).unwrap();
+        assert!(rx_expl_out.recv_timeout(Duration::from_millis(100)).is_ok());
+        assert_eq!(ai_handle.referral(1).unwrap().planet_id, Some(7));
+        assert_eq!(ai_handle.referrals().len(), 3);
+        assert_eq!(ai_handle.quota_usage(1).unwrap().refusals, 0);
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_quote_requests() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, EnterpriseConfig::default()); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //No energy yet
+        let carbon = ai_handle.quote(QuoteRequest::Generate(BasicResourceType::Carbon));
+        assert!(carbon.supported);
+        assert!(!carbon.cell_available);
+        assert!(!carbon.would_succeed());
+
+        //Incoming explorer, so that the next sunray is saved for it
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //Sunray
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        let carbon = ai_handle.quote(QuoteRequest::Generate(BasicResourceType::Carbon));
+        assert!(carbon.would_succeed());
+        assert_eq!(carbon.energy_cost, 1);
+
+        let hydrogen = ai_handle.quote(QuoteRequest::Generate(BasicResourceType::Hydrogen));
+        assert!(!hydrogen.supported);
+        assert_eq!(hydrogen.energy_cost, 0);
+
+        //The inputs can be given in any order, but must match the recipe
+        let water = ai_handle.quote(QuoteRequest::Combine {
+            resource: ComplexResourceType::Water,
+            inputs: (ResourceType::Basic(BasicResourceType::Oxygen), ResourceType::Basic(BasicResourceType::Hydrogen)),
+        });
+        assert!(water.would_succeed());
+        let water = ai_handle.quote(QuoteRequest::Combine {
+            resource: ComplexResourceType::Water,
+            inputs: (ResourceType::Basic(BasicResourceType::Carbon), ResourceType::Basic(BasicResourceType::Hydrogen)),
+        });
+        assert!(water.supported);
+        assert!(!water.inputs_valid);
+
+        //Quotes don't consume anything, a real request does
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+        assert!(!ai_handle.quote(QuoteRequest::Generate(BasicResourceType::Carbon)).cell_available);
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_combination_validation() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let mut dummy_planet = create_planet(67, rx_orch_in, tx_orch_out, rx_expl_in); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The explorer gets two carbons, using both charged cells
+        let mut carbons = Vec::new();
+        for _ in 0..2 {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: Some(r) }) => {
+                    carbons.push(r.to_carbon().unwrap())
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //No charged cell: the request is rejected and the carbons are given back untouched
+        let c2 = carbons.pop().unwrap();
+        let c1 = carbons.pop().unwrap();
+        tx_expl_in.send(ExplorerToPlanet::CombineResourceRequest {explorer_id: 1, msg: ComplexResourceRequest::Diamond(c1, c2),}).unwrap();
+        let (c1, c2) = match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::CombineResourceResponse { complex_response: Err((message, r1, r2)) }) => {
+                assert_eq!(message, CombineRejection::NoEnergyCell.message());
+                (r1.to_carbon().unwrap(), r2.to_carbon().unwrap())
+            }
+            _ => panic!("the combination should have been rejected"),
+        };
+
+        //Sunray, then the same request succeeds
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::CombineResourceRequest {explorer_id: 1, msg: ComplexResourceRequest::Diamond(c1, c2),}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::CombineResourceResponse { complex_response: Ok(complex) }) => {
+                assert_eq!(complex.get_type(), ComplexResourceType::Diamond)
+            }
+            _ => assert!(false),
+        }
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_carbon_stockpile() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            carbon_stockpile: Some(1),
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Four sunrays with nobody on the planet: rocket, charged cell, one carbon stockpiled, then the stockpile is full
+        for _ in 0..4 {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+        }
+        assert_eq!(ai_handle.stockpiled_carbon(), 1);
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The first carbon comes from the stockpile
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+        assert_eq!(ai_handle.stockpiled_carbon(), 0);
+        assert_eq!(ai_handle.quota_usage(1).unwrap().cells_used, 0); //No cell was used for it
+
+        //Check internal state
+        tx_orch_in.send(OrchestratorToPlanet::InternalStateRequest).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::InternalStateResponse { planet_id:67, planet_state:dummy_state }) => {
+                assert_eq!(dummy_state.charged_cells_count, 1); //The live cell wasn't touched
+                assert!(dummy_state.has_rocket);
+            }
+            _ => assert!(false),
+        }
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_assisted_crafting() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (_tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+
+        let config = EnterpriseConfig {
+            carbon_stockpile: Some(2),
+            assist: AssistConfig {
+                recipes: HashMap::from([(ComplexResourceType::Diamond, 2)]),
+                max_carbon_per_explorer: Some(2),
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Rocket, charged cell and two stockpiled carbons
+        for _ in 0..4 {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+        }
+        assert_eq!(ai_handle.stockpiled_carbon(), 2);
+
+        //Water is not assisted, the inputs come back right away
+        assert!(ai_handle.order_assisted_craft(1, ComplexResourceType::Water, Vec::new()).is_err());
+
+        //A Diamond made only of stockpiled carbon, crafted on the next sunray
+        assert!(ai_handle.order_assisted_craft(1, ComplexResourceType::Diamond, Vec::new()).is_ok());
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        let crafts = ai_handle.take_assisted_crafts(1);
+        assert_eq!(crafts.len(), 1);
+        assert_eq!(crafts[0].carbon_supplied, 2);
+        match &crafts[0].result {
+            Ok(complex) => assert_eq!(complex.get_type(), ComplexResourceType::Diamond),
+            Err(_) => assert!(false),
+        }
+        assert!(ai_handle.take_assisted_crafts(1).is_empty());
+        assert_eq!(ai_handle.stockpiled_carbon(), 0);
+
+        let usage = ai_handle.assist_usage(1).unwrap();
+        assert_eq!(usage.crafts, 1);
+        assert_eq!(usage.carbon_supplied, 2);
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_combination_request_gives_back_inputs() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let (mut dummy_planet, _ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, EnterpriseConfig::default()); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The explorer gets two carbons
+        let mut carbons = Vec::new();
+        for _ in 0..2 {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: Some(r) }) => {
+                    carbons.push(r.to_carbon().unwrap().to_generic())
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //Carbon doesn't make Water, both inputs are given back
+        let c2 = carbons.pop().unwrap();
+        let c1 = carbons.pop().unwrap();
+        let (c1, c2) = match combination_request(ComplexResourceType::Water, c1, c2) {
+            Err(inputs) => inputs,
+            Ok(_) => panic!("carbon shouldn't make water"),
+        };
+        let carbon = ResourceType::Basic(BasicResourceType::Carbon);
+        assert_eq!((c1.get_type(), c2.get_type()), (carbon, carbon));
+
+        //They still make a Diamond
+        assert!(matches!(combination_request(ComplexResourceType::Diamond, c1, c2), Ok(ComplexResourceRequest::Diamond(_, _))));
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_craft_planner() {
+        let planner = CraftPlanner::enterprise();
+        let basic = |b| ResourceType::Basic(b);
+        let complex = |c| ResourceType::Complex(c);
+
+        //Enterprise can't generate Hydrogen, so Water and everything above it need an inventory
+        let empty = HashMap::new();
+        assert_eq!(
+            planner.plan(complex(ComplexResourceType::AIPartner), &empty),
+            Err(PlanError::Unobtainable(basic(BasicResourceType::Silicon)))
+        );
+
+        //Diamond only needs Carbon
+        let diamond = planner.plan(complex(ComplexResourceType::Diamond), &empty).unwrap();
+        assert_eq!(diamond.steps, vec![
+            CraftStep::Generate(BasicResourceType::Carbon),
+            CraftStep::Generate(BasicResourceType::Carbon),
+            CraftStep::Combine(ComplexResourceType::Diamond),
+        ]);
+        assert_eq!(diamond.energy_cells, 3);
+
+        //AIPartner = Robot(Silicon, Life(Water, Carbon)) + Diamond(Carbon, Carbon)
+        let inventory = HashMap::from([
+            (basic(BasicResourceType::Silicon), 1),
+            (complex(ComplexResourceType::Water), 1),
+        ]);
+        let ai_partner = planner.plan(complex(ComplexResourceType::AIPartner), &inventory).unwrap();
+        assert_eq!(ai_partner.steps, vec![
+            CraftStep::Generate(BasicResourceType::Carbon),
+            CraftStep::Combine(ComplexResourceType::Life),
+            CraftStep::Combine(ComplexResourceType::Robot),
+            CraftStep::Generate(BasicResourceType::Carbon),
+            CraftStep::Generate(BasicResourceType::Carbon),
+            CraftStep::Combine(ComplexResourceType::Diamond),
+            CraftStep::Combine(ComplexResourceType::AIPartner),
+        ]);
+        assert_eq!(ai_partner.energy_cells, 7);
+        assert_eq!(ai_partner.from_inventory, inventory);
+
+        //A resource already in the inventory needs no step
+        let water = planner.plan(complex(ComplexResourceType::Water), &inventory).unwrap();
+        assert!(water.steps.is_empty());
+        assert_eq!(water.energy_cells, 0);
+    }
+
+    #[test]
+    fn test_recipe_graph_export() {
+        let graph = RecipeGraph::enterprise();
+
+        //6 complex resources, Carbon and the 3 basic inputs Enterprise can't generate
+        assert_eq!(graph.nodes.len(), 10);
+        let hydrogen = graph.nodes.iter().find(|node| node.resource == ResourceType::Basic(BasicResourceType::Hydrogen)).unwrap();
+        assert!(!hydrogen.producible);
+        let carbon = graph.nodes.iter().find(|node| node.resource == ResourceType::Basic(BasicResourceType::Carbon)).unwrap();
+        assert!(carbon.producible);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph recipes {"));
+        assert!(dot.contains("\"Carbon\" -> \"Diamond\" [label=\"x2\"];"));
+        assert!(dot.contains("\"Hydrogen\" [shape=ellipse];"));
+
+        let json = graph.to_json();
+        assert!(json.contains("{\"id\": \"Carbon\", \"kind\": \"basic\", \"producible\": true}"));
+        assert!(json.contains("{\"from\": \"Robot\", \"to\": \"AIPartner\", \"quantity\": 1}"));
+    }
+
+    #[test]
+    fn test_precrafted_cache() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            carbon_stockpile: Some(2),
+            cache: CacheConfig {
+                resources: vec![ComplexResourceType::Diamond],
+                capacity: 1,
+                ..CacheConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Rocket, charged cell, two stockpiled carbons, then a Diamond pre-crafted out of them
+        for _ in 0..5 {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+        }
+        let stats = ai_handle.cache_stats();
+        assert_eq!(stats.crafted, 1);
+        assert_eq!(stats.cached.get(&ComplexResourceType::Diamond), Some(&1));
+        assert_eq!(ai_handle.stockpiled_carbon(), 0);
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The explorer gets two carbons, using both charged cells
+        let mut carbons = Vec::new();
+        for sunray in [false, true] {
+            if sunray {
+                tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+                match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                    Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                        assert!(true)
+                    }
+                    _ => assert!(false),
+                }
+            }
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: Some(r) }) => {
+                    carbons.push(r.to_carbon().unwrap())
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //No charged cell, but the Diamond comes from the cache
+        let c2 = carbons.pop().unwrap();
+        let c1 = carbons.pop().unwrap();
+        tx_expl_in.send(ExplorerToPlanet::CombineResourceRequest {explorer_id: 1, msg: ComplexResourceRequest::Diamond(c1, c2),}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::CombineResourceResponse { complex_response: Ok(complex) }) => {
+                assert_eq!(complex.get_type(), ComplexResourceType::Diamond)
+            }
+            _ => assert!(false),
+        }
+        let stats = ai_handle.cache_stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.donations, 2); //The explorer carbons are kept for the next Diamond
+        assert!(stats.cached.is_empty());
+        //The cache hit is billed, but it isn't counted as a cell used by the explorer
+        assert_eq!(ai_handle.quota_usage(1).map(|usage| usage.cells_used), Some(2));
+        assert_eq!(ai_handle.transactions(1).last().map(|t| t.outcome.clone()), Some(TransactionOutcome::FromCache));
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_cell_auction() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        //First explorer
+        let (tx_expl_out_f, rx_expl_out_f) = unbounded::<PlanetToExplorer>();
+        //Second explorer
+        let (tx_expl_out_s, rx_expl_out_s) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            auction: AuctionConfig {
+                bidding_window: Some(Duration::from_millis(50)),
+                ..AuctionConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorers
+        for (id, sender) in [(1, tx_expl_out_f), (2, tx_expl_out_s)] {
+            tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: id, new_sender: sender,}).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: i, res:r }) => {
+                    assert_eq!(i, id); //Checking the explorer id
+                    assert_eq!(r, Ok(())); //Checking response
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //Send sunray
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Both explorers bid, the second one with a higher priority
+        assert!(ai_handle.bid(1, 1, Vec::new()).is_ok());
+        assert!(ai_handle.bid(2, 5, Vec::new()).is_ok());
+
+        //The first explorer can't grab the cell while the bids are open
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_f.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+
+        //Once the window is over the cell goes to the second explorer only
+        thread::sleep(Duration::from_millis(60));
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_f.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+        assert_eq!(ai_handle.cell_reserved_for(), Some(2));
+
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 2, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_s.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+        assert_eq!(ai_handle.cell_reserved_for(), None);
+
+        let results = ai_handle.auction_results();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].winner, 2);
+        assert_eq!(results[0].bids, 2);
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_auction_reservation_kept() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        //First explorer
+        let (tx_expl_out_f, rx_expl_out_f) = unbounded::<PlanetToExplorer>();
+        //Second explorer
+        let (tx_expl_out_s, rx_expl_out_s) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            auction: AuctionConfig {
+                bidding_window: Some(Duration::from_millis(50)),
+                ..AuctionConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorers
+        for (id, sender) in [(1, tx_expl_out_f), (2, tx_expl_out_s)] {
+            tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: id, new_sender: sender,}).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: i, res:r }) => {
+                    assert_eq!(i, id); //Checking the explorer id
+                    assert_eq!(r, Ok(())); //Checking response
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //Send sunray
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //The second explorer wins the cell
+        assert!(ai_handle.bid(2, 5, Vec::new()).is_ok());
+        thread::sleep(Duration::from_millis(60));
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_f.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+        assert_eq!(ai_handle.cell_reserved_for(), Some(2));
+
+        //A new bid during the reservation doesn't take the cell away from the winner
+        assert!(ai_handle.bid(1, 1, Vec::new()).is_ok());
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 2, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_s.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+        assert_eq!(ai_handle.cell_reserved_for(), None);
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_auction_payment_refunded() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        //No stockpile and no cache, the planet can't keep a payment
+        let config = EnterpriseConfig {
+            auction: AuctionConfig {
+                bidding_window: Some(Duration::from_millis(50)),
+                ..AuctionConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The explorer gets a carbon to pay with
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        let carbon = match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: Some(r) }) => r.to_carbon().unwrap(),
+            _ => panic!("the explorer should get a carbon"),
+        };
+
+        //The explorer wins the auction, its payment is refunded
+        assert!(ai_handle.bid(1, 1, vec![carbon.to_generic()]).is_ok());
+        thread::sleep(Duration::from_millis(60));
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        assert_eq!(ai_handle.auction_results()[0].offered, 1);
+        assert_eq!(ai_handle.stockpiled_carbon(), 0);
+        let refunds = ai_handle.take_refunds(1);
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].get_type(), ResourceType::Basic(BasicResourceType::Carbon));
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_auction_payment_kept() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        //The cache keeps the payments that aren't Carbon
+        let config = EnterpriseConfig {
+            auction: AuctionConfig {
+                bidding_window: Some(Duration::from_millis(50)),
+                ..AuctionConfig::default()
+            },
+            cache: CacheConfig {
+                resources: vec![ComplexResourceType::Life],
+                ..CacheConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The explorer crafts a diamond to pay with
+        let mut carbons = Vec::new();
+        for _ in 0..2 {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: Some(r) }) => {
+                    carbons.push(r.to_carbon().unwrap())
+                }
+                _ => assert!(false),
+            }
+        }
+        let c2 = carbons.pop().unwrap();
+        let c1 = carbons.pop().unwrap();
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::CombineResourceRequest {explorer_id: 1, msg: ComplexResourceRequest::Diamond(c1, c2),}).unwrap();
+        let diamond = match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::CombineResourceResponse { complex_response: Ok(complex) }) => complex,
+            _ => panic!("the explorer should get a diamond"),
+        };
+
+        //The explorer wins the auction, the planet keeps its diamond
+        assert!(ai_handle.bid(1, 1, vec![GenericResource::ComplexResources(diamond)]).is_ok());
+        thread::sleep(Duration::from_millis(60));
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        assert_eq!(ai_handle.auction_results()[0].offered, 1);
+        assert!(ai_handle.take_refunds(1).is_empty());
+        let stats = ai_handle.cache_stats();
+        assert_eq!(stats.payments, 1);
+        assert_eq!(stats.donations, 0);
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_priority_classes() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        //Scout explorer
+        let (tx_expl_out_f, rx_expl_out_f) = unbounded::<PlanetToExplorer>();
+        //Critical explorer
+        let (tx_expl_out_s, rx_expl_out_s) = unbounded::<PlanetToExplorer>();
+
+        let config = EnterpriseConfig {
+            priorities: HashMap::from([(1, PriorityClass::Low)]),
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+        ai_handle.set_priority(2, PriorityClass::Critical);
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming scout
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out_f,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //With only a scout on the planet the sunray goes to a rocket
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_orch_in.send(OrchestratorToPlanet::InternalStateRequest).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::InternalStateResponse { planet_id:67, planet_state:dummy_state }) => {
+                assert!(dummy_state.has_rocket);
+                assert_eq!(dummy_state.charged_cells_count, 0);
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming critical explorer, then a sunray
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 2, new_sender: tx_expl_out_s,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 2); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //The critical explorer never asked for energy, so the scout gets the cell
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_f.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+
+        //The critical explorer was served, so it no longer keeps the cell from the scout
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 2, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_s.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_f.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+
+        //The critical explorer asks while there is no cell, the next one is kept for it
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 2, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_s.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_f.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 2, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out_s.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+
+        let stats = ai_handle.priority_stats();
+        assert_eq!(stats[&PriorityClass::Low].kept_for_higher, 1);
+        assert_eq!(stats[&PriorityClass::Low].served, 2);
+        assert_eq!(stats[&PriorityClass::Critical].served, 2);
+        assert!(!stats.contains_key(&PriorityClass::Normal));
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_transaction_ledger() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, EnterpriseConfig::default()); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The explorer gets two carbons, using both charged cells
+        let mut carbons = Vec::new();
+        for _ in 0..2 {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: Some(r) }) => {
+                    carbons.push(r.to_carbon().unwrap())
+                }
+                _ => assert!(false),
+            }
+        }
+
+        //No charged cell: the generation fails
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+
+        //Sunray, then the carbons are combined into a diamond
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        let c2 = carbons.pop().unwrap();
+        let c1 = carbons.pop().unwrap();
+        tx_expl_in.send(ExplorerToPlanet::CombineResourceRequest {explorer_id: 1, msg: ComplexResourceRequest::Diamond(c1, c2),}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::CombineResourceResponse { complex_response: Ok(complex) }) => {
+                assert_eq!(complex.get_type(), ComplexResourceType::Diamond)
+            }
+            _ => assert!(false),
+        }
+
+        //Every request is in the ledger
+        let transactions = ai_handle.transactions(1);
+        assert_eq!(transactions.len(), 4);
+        assert_eq!(transactions[2].outcome, TransactionOutcome::Failed("No charged energy cell".to_string()));
+        assert_eq!(transactions[3].kind, TransactionKind::Combine);
+        assert_eq!(transactions[3].inputs, vec![ResourceType::Basic(BasicResourceType::Carbon); 2]);
+        assert_eq!(transactions[3].output, Some(ResourceType::Complex(ComplexResourceType::Diamond)));
+
+        let ledger = ai_handle.ledger();
+        assert_eq!(ledger.energy_spent(1), 3);
+        let csv = ledger.to_csv();
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv.lines().last().unwrap().contains(",1,combine,Carbon;Carbon,Diamond,1,generated,"));
+        assert!(ledger.to_json().contains("\"error\": \"No charged energy cell\""));
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_energy_billing() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        let diamond = ResourceType::Complex(ComplexResourceType::Diamond);
+        let carbon = ResourceType::Basic(BasicResourceType::Carbon);
+        let config = EnterpriseConfig {
+            billing: BillingConfig {
+                costs: HashMap::from([(diamond, 3)]),
+                budget: Some(4),
+                ..BillingConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The explorer buys two carbons, one credit each
+        let mut carbons = Vec::new();
+        for _ in 0..3 {
+            tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+            match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+                Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                    assert!(true)
+                }
+                _ => assert!(false),
+            }
+            if carbons.len() == 2 {
+                break;
+            }
+
+            tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+            match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+                Ok(PlanetToExplorer::GenerateResourceResponse { resource: Some(r) }) => {
+                    carbons.push(r.to_carbon().unwrap())
+                }
+                _ => assert!(false),
+            }
+        }
+        assert_eq!(ai_handle.account(1).remaining(), Some(2));
+
+        //A diamond costs three credits: over budget, the carbons are given back
+        let c2 = carbons.pop().unwrap();
+        let c1 = carbons.pop().unwrap();
+        tx_expl_in.send(ExplorerToPlanet::CombineResourceRequest {explorer_id: 1, msg: ComplexResourceRequest::Diamond(c1, c2),}).unwrap();
+        let (c1, c2) = match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::CombineResourceResponse { complex_response: Err((message, r1, r2)) }) => {
+                assert_eq!(message, RefusalReason::BudgetExceeded.message());
+                (r1.to_carbon().unwrap(), r2.to_carbon().unwrap())
+            }
+            _ => panic!("the combination should have been refused"),
+        };
+
+        //The host raises the budget, then the same request succeeds
+        ai_handle.set_budget(1, Some(5));
+        tx_expl_in.send(ExplorerToPlanet::CombineResourceRequest {explorer_id: 1, msg: ComplexResourceRequest::Diamond(c1, c2),}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::CombineResourceResponse { complex_response: Ok(complex) }) => {
+                assert_eq!(complex.get_type(), ComplexResourceType::Diamond)
+            }
+            _ => assert!(false),
+        }
+
+        let account = ai_handle.account(1);
+        assert_eq!(account.spent, 5);
+        assert_eq!(account.remaining(), Some(0));
+        assert_eq!(account.items[&carbon], BillItem { quantity: 2, credits: 2 });
+        assert_eq!(account.items[&diamond], BillItem { quantity: 1, credits: 3 });
+        assert_eq!(ai_handle.bills().len(), 1);
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_deferred_requests() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+        let (tx_delivery, rx_delivery) = unbounded::<DeferredDelivery>();
+
+        let config = EnterpriseConfig {
+            waitlist: WaitlistConfig {
+                delivery: Some(tx_delivery),
+                ..WaitlistConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, _ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //No charged cell: the explorer gets nothing for now
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+        assert!(rx_delivery.try_recv().is_err());
+
+        //The next sunray charges the cell and the carbon is delivered through the host
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        match rx_delivery.recv_timeout(Duration::from_millis(100)) {
+            Ok(DeferredDelivery { explorer_id: 1, resource: BasicResourceType::Carbon, outcome: DeferredOutcome::Delivered(r), .. }) => {
+                assert!(r.to_carbon().is_ok())
+            }
+            _ => assert!(false),
+        }
+
+        //A new request waits again, and is cancelled when the explorer leaves
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+        tx_orch_in.send(OrchestratorToPlanet::OutgoingExplorerRequest { explorer_id: 1 }).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::OutgoingExplorerResponse { planet_id:67, explorer_id:id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+        match rx_delivery.recv_timeout(Duration::from_millis(100)) {
+            Ok(DeferredDelivery { explorer_id: 1, outcome: DeferredOutcome::Cancelled, .. }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_energy_eta() {
+        let start = Instant::now();
+        let mut sunrays = SunrayHistory::default();
+
+        //A single sunray says nothing about the next one
+        sunrays.record(start);
+        assert_eq!(sunrays.energy_eta(false, start), None);
+
+        //Sunrays every ten seconds
+        sunrays.record(start + Duration::from_secs(10));
+        sunrays.record(start + Duration::from_secs(20));
+        assert_eq!(sunrays.mean_interval(), Some(Duration::from_secs(10)));
+        assert_eq!(sunrays.next_arrival(start + Duration::from_secs(25)), Some(start + Duration::from_secs(30)));
+        assert_eq!(sunrays.energy_eta(false, start + Duration::from_secs(25)), Some(Duration::from_secs(5)));
+
+        //A charged cell is available right away
+        assert_eq!(sunrays.energy_eta(true, start + Duration::from_secs(25)), Some(Duration::ZERO));
+
+        //An overdue sunray is expected at the following interval
+        assert_eq!(sunrays.next_arrival(start + Duration::from_secs(45)), Some(start + Duration::from_secs(50)));
+    }
+
+    #[test]
+    fn test_sunray_pattern() {
+        let start = Instant::now();
+        let mut sunrays = SunrayHistory::default();
+
+        //Regular sunrays: no burst and no cycle
+        let mut at = start;
+        for _ in 0..5 {
+            sunrays.record(at);
+            at += Duration::from_secs(2);
+        }
+        let pattern = sunrays.pattern().unwrap();
+        assert_eq!(pattern.samples, 4);
+        assert_eq!(pattern.rate_per_minute, 30.0);
+        assert_eq!(pattern.burstiness, 0.0);
+        assert_eq!(pattern.period, None);
+        assert!(!pattern.night_ahead(2.0));
+
+        //Days of three quick sunrays followed by a long night
+        let mut sunrays = SunrayHistory::default();
+        let mut at = start;
+        sunrays.record(at);
+        for interval in [1, 1, 1, 10, 1, 1, 1, 10, 1, 1, 1, 10, 1, 1, 1] {
+            at += Duration::from_secs(interval);
+            sunrays.record(at);
+        }
+        let pattern = sunrays.pattern().unwrap();
+        assert_eq!(pattern.period, Some(4));
+        assert_eq!(pattern.cycle, Some(Duration::from_secs(13)));
+        assert_eq!(pattern.next_interval, Duration::from_secs(10));
+        assert!(pattern.burstiness > 1.0);
+        assert!(pattern.confidence > 0.4 && pattern.confidence < 1.0);
+        assert!(pattern.night_ahead(2.0));
+
+        //The energy ETA follows the cycle
+        assert_eq!(sunrays.energy_eta(false, at), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_adaptive_policy() {
+        let config = AdaptiveConfig {
+            policies: vec![EnergyPolicy::DefenseFirst, EnergyPolicy::ExplorersFirst],
+            epoch_sunrays: 2,
+            exploration: 0.0,
+            seed: 42,
+            ..AdaptiveConfig::default()
+        };
+
+        //Explorers get served only under ExplorersFirst: the learner settles on it
+        let mut learner = PolicyLearner::new(config.clone());
+        for _ in 0..10 {
+            for _ in 0..2 {
+                if learner.policy() == EnergyPolicy::ExplorersFirst {
+                    learner.record_cell_served();
+                } else {
+                    learner.record_unserved();
+                }
+                learner.record_sunray(false);
+            }
+        }
+        assert_eq!(learner.policy(), EnergyPolicy::ExplorersFirst);
+        let stats = learner.stats();
+        assert!(stats.iter().all(|stats| stats.epochs > 0));
+        assert!(stats[1].mean_reward > stats[0].mean_reward);
+
+        //The same seed and events make the same choices
+        let explore = AdaptiveConfig {
+            exploration: 0.5,
+            ..config
+        };
+        let mut first = PolicyLearner::new(explore.clone());
+        let mut second = PolicyLearner::new(explore);
+        for sunray in 0..100 {
+            assert_eq!(first.policy(), second.policy());
+            first.record_sunray(sunray % 3 == 0);
+            second.record_sunray(sunray % 3 == 0);
+        }
+
+        //Disabled, the planet keeps its configured behaviour
+        let mut disabled = PolicyLearner::new(AdaptiveConfig::default());
+        assert!(!disabled.record_sunray(true));
+        assert_eq!(disabled.policy(), EnergyPolicy::Balanced);
+    }
+
+    #[test]
+    fn test_risk_score() {
+        let start = Instant::now();
+        let mut risk = RiskModel::new(RiskConfig::default());
+
+        //Defended planet, nobody around, no asteroid seen
+        let assessment = risk.assess(true, true, false, start);
+        assert_eq!(assessment.score, 0.0);
+        assert_eq!(assessment.level, RiskLevel::Low);
+
+        //No rocket, no charged cell and explorers on the planet
+        let assessment = risk.assess(false, false, true, start);
+        assert!((assessment.score - 0.5).abs() < 1e-9);
+        assert_eq!(assessment.level, RiskLevel::Elevated);
+
+        //A shower of asteroids makes it high
+        for _ in 0..3 {
+            risk.record_asteroid(start);
+        }
+        let assessment = risk.assess(false, false, true, start);
+        assert!((assessment.score - 1.0).abs() < 1e-9);
+        assert_eq!(assessment.level, RiskLevel::High);
+
+        //The last asteroid fades over the window
+        let assessment = risk.assess(false, false, true, start + Duration::from_secs(30));
+        assert!((assessment.factors.recent_asteroid - 0.5).abs() < 1e-9);
+        assert_eq!(assessment.level, RiskLevel::High);
+        let assessment = risk.assess(false, false, true, start + Duration::from_secs(61));
+        assert_eq!(assessment.factors.asteroid_frequency, 0.0);
+        assert_eq!(assessment.level, RiskLevel::Elevated);
+
+        //The thresholds are configurable
+        let mut cautious = RiskModel::new(RiskConfig {
+            high: 0.5,
+            ..RiskConfig::default()
+        });
+        assert_eq!(cautious.assess(false, false, true, start).level, RiskLevel::High);
+        assert_eq!(risk.last().map(|last| last.level), Some(RiskLevel::Elevated));
+    }
+
+    #[test]
+    fn test_cell_kept_for_defense() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+
+        //Without a rocket, explorers on the planet are enough to make the risk high
+        let config = EnterpriseConfig {
+            risk: RiskConfig {
+                high: 0.35,
+                keep_cell_for_defense: true,
+                ..RiskConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The sunray charges the cell for the explorer, but there is no rocket
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //The last charged cell is kept for an emergency rocket
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_none())
+            }
+            _ => assert!(false),
+        }
+        let transactions = ai_handle.transactions(1);
+        assert_eq!(transactions[0].outcome, TransactionOutcome::Failed(RefusalReason::KeptForDefense.message()));
+        assert_eq!(ai_handle.risk().map(|risk| risk.level), Some(RiskLevel::High));
+
+        //Once a rocket is built, the explorer gets its carbon
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_journal_keeps_energy_decisions() {
+        let start = Instant::now();
+        let (tx_report, _rx_report) = unbounded::<PostMortem>();
+        let mut journal = Journal::new(PostMortemConfig {
+            sink: Some(tx_report),
+            max_events: 2,
+        });
+
+        //Two sunrays kept for the explorers, then only explorers coming and going
+        for decision in [ExplorerDemand::RecentDemand, ExplorerDemand::ExplorersPresent] {
+            journal.record(PlanetEvent::Sunray { decision, used: true, rocket_built: false }, start);
+        }
+        for explorer_id in 1..=3 {
+            journal.record(PlanetEvent::ExplorerArrived { explorer_id }, start);
+        }
+
+        //The recent events moved on, but the energy decisions are still there to blame
+        let report = journal.post_mortem(67, journal.cause(true), Vec::new(), None, start);
+        assert_eq!(report.recent_events.len(), 2);
+        assert_eq!(report.energy_decisions.len(), 2);
+        assert_eq!(report.cause, DefenseGap::RocketNotBuilt { rule: ExplorerDemand::ExplorersPresent });
+    }
+
+    #[test]
+    fn test_post_mortem() {
+        let (tx_orch_in, rx_orch_in) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch_out, rx_orch_out) = unbounded::<PlanetToOrchestrator>();
+        let (tx_expl_in, rx_expl_in) = unbounded::<ExplorerToPlanet>();
+        let (tx_expl_out, rx_expl_out) = unbounded::<PlanetToExplorer>();
+        let (tx_report, rx_report) = unbounded::<PostMortem>();
+
+        let config = EnterpriseConfig {
+            post_mortem: PostMortemConfig {
+                sink: Some(tx_report),
+                ..PostMortemConfig::default()
+            },
+            ..EnterpriseConfig::default()
+        };
+        let (mut dummy_planet, _ai_handle) = create_planet_with_config(67, rx_orch_in, tx_orch_out, rx_expl_in, config); // Creating the planet
+
+        let _handle = thread::spawn(move || dummy_planet.run());
+
+        // Start
+        tx_orch_in.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //Incoming explorer
+        tx_orch_in.send(OrchestratorToPlanet::IncomingExplorerRequest {explorer_id: 1, new_sender: tx_expl_out,}).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::IncomingExplorerResponse { planet_id:67, explorer_id: id, res:r }) => {
+                assert_eq!(id, 1); //Checking the explorer id
+                assert_eq!(r, Ok(())); //Checking response
+            }
+            _ => assert!(false),
+        }
+
+        //The sunray charges the cell, which is kept for the explorer
+        tx_orch_in.send(OrchestratorToPlanet::Sunray(Sunray::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::SunrayAck { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+
+        //The explorer spends the only charged cell
+        tx_expl_in.send(ExplorerToPlanet::GenerateResourceRequest {explorer_id: 1, resource: BasicResourceType::Carbon,}).unwrap();
+        match rx_expl_out.recv_timeout(Duration::from_millis(100)) {
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: r }) => {
+                assert!(r.is_some())
+            }
+            _ => assert!(false),
+        }
+        assert!(rx_report.try_recv().is_err()); //Nothing to report while the planet is alive
+
+        //The asteroid finds the planet without defense
+        tx_orch_in.send(OrchestratorToPlanet::Asteroid(Asteroid::default())).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::AsteroidAck { planet_id:67, rocket:r }) => {
+                assert!(r.is_none());
+            }
+            _ => assert!(false),
+        }
+
+        let report = rx_report.recv_timeout(Duration::from_millis(50)).unwrap();
+        assert_eq!(report.planet_id, 67);
+        assert_eq!(report.explorers_present, vec![1]);
+        assert_eq!(
+            report.cause,
+            DefenseGap::CellSpentByExplorer { explorer_id: 1, rule: Some(ExplorerDemand::ExplorersPresent) }
+        );
+        let decisions = report.energy_decisions.iter().map(|timed| timed.event.as_str()).collect::<Vec<_>>();
+        assert_eq!(decisions, vec!["sunray", "cell_spent"]);
+        assert!(matches!(report.recent_events.first().map(|timed| timed.event), Some(PlanetEvent::ExplorerArrived { explorer_id: 1 })));
+        assert!(matches!(report.recent_events.last().map(|timed| timed.event), Some(PlanetEvent::Asteroid { defended: false })));
+        assert!(report.to_json().contains("\"rule\": \"explorers_present\""));
+
+        // Kill
+        tx_orch_in.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        match rx_orch_out.recv_timeout(Duration::from_millis(50)) {
+            Ok(PlanetToOrchestrator::KillPlanetResult { planet_id: 67 }) => {
+                assert!(true)
+            }
+            _ => assert!(false),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use common_game::components::resource::ResourceType;
+use common_game::utils::ID;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Requests explorers sent for a single resource the planet can't produce
+#[derive(Debug, Clone, Default)]
+pub struct UnsupportedRequests {
+    pub requests: u64,
+    pub by_explorer: HashMap<ID, u64>, // Requests sent by each explorer
+    pub first_request: Option<Instant>,
+    pub last_request: Option<Instant>,
+}
+
+/// Aggregated demand for the resources Enterprise can't generate or combine
+#[derive(Debug, Clone, Default)]
+pub struct UnsupportedDemandReport {
+    pub resources: HashMap<ResourceType, UnsupportedRequests>,
+}
+
+impl UnsupportedDemandReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, resource: ResourceType, explorer_id: ID, now: Instant) {
+        let stats = self.resources.entry(resource).or_default();
+        stats.requests += 1;
+        *stats.by_explorer.entry(explorer_id).or_default() += 1;
+        stats.first_request.get_or_insert(now);
+        stats.last_request = Some(now);
+    }
+
+    pub fn get(&self, resource: ResourceType) -> Option<&UnsupportedRequests> {
+        self.resources.get(&resource)
+    }
+
+    // Total number of unsupported requests received
+    pub fn total(&self) -> u64 {
+        self.resources.values().map(|stats| stats.requests).sum()
+    }
+
+    // Unsupported resources with their request count, most wanted first
+    pub fn most_requested(&self) -> Vec<(ResourceType, u64)> {
+        let mut ranking: Vec<_> = self
+            .resources
+            .iter()
+            .map(|(resource, stats)| (*resource, stats.requests))
+            .collect();
+        ranking.sort_by_key(|(_, requests)| Reverse(*requests));
+        ranking
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{request_inputs, requested_type};
+use common_game::components::resource::{
+    Combinator, ComplexResourceRequest, ComplexResourceType, GenericResource,
+};
+
+/// Why a combination request is rejected before any energy is touched.
+/// The inputs always match the recipe: `ComplexResourceRequest` is typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombineRejection {
+    UnsupportedRecipe(ComplexResourceType), // The combinator has no recipe for the resource
+    NoEnergyCell,                           // No charged cell to power the combination
+}
+
+impl CombineRejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CombineRejection::UnsupportedRecipe(_) => "unsupported_recipe",
+            CombineRejection::NoEnergyCell => "no_energy_cell_available",
+        }
+    }
+
+    // Message sent back to the explorer with its resources
+    pub fn message(self) -> String {
+        match self {
+            CombineRejection::UnsupportedRecipe(resource) => {
+                format!("No recipe for {:?}", resource)
+            }
+            CombineRejection::NoEnergyCell => "No energy cell available".to_string(),
+        }
+    }
+}
+
+// Checks a combination request against the enabled recipes and the available energy.
+// Gives back the request and the charged cell to use, or the rejection with the untouched inputs.
+#[allow(clippy::type_complexity)]
+pub(crate) fn validate_combination(
+    request: ComplexResourceRequest,
+    combinator: &Combinator,
+    charged_cell: Option<usize>,
+) -> Result<(ComplexResourceRequest, usize), (CombineRejection, GenericResource, GenericResource)> {
+    let resource = requested_type(&request);
+    let rejection = if !combinator.contains(resource) {
+        Some(CombineRejection::UnsupportedRecipe(resource))
+    } else if charged_cell.is_none() {
+        Some(CombineRejection::NoEnergyCell)
+    } else {
+        None
+    };
+
+    match (rejection, charged_cell) {
+        (None, Some(at)) => Ok((request, at)),
+        (rejection, _) => {
+            let (_, r1, r2) = request_inputs(request);
+            Err((rejection.unwrap_or(CombineRejection::NoEnergyCell), r1, r2))
+        }
+    }
+}
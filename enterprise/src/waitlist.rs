@@ -0,0 +1,139 @@
+use crate::refusal::RefusalReason;
+use common_game::components::resource::{BasicResource, BasicResourceType};
+use common_game::utils::ID;
+use crossbeam_channel::Sender;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Waitlist for the generation requests made while no cell is charged
+#[derive(Debug, Clone)]
+pub struct WaitlistConfig {
+    pub delivery: Option<Sender<DeferredDelivery>>, // Where the host receives the outcomes, `None` disables the waitlist
+    pub timeout: Duration,                          // Requests not fulfilled in time are dropped
+    pub max_per_explorer: usize,                    // Requests an explorer can have waiting at the same time
+}
+
+impl Default for WaitlistConfig {
+    fn default() -> Self {
+        Self {
+            delivery: None,
+            timeout: Duration::from_secs(30),
+            max_per_explorer: 1,
+        }
+    }
+}
+
+/// What became of a deferred request
+#[derive(Debug)]
+pub enum DeferredOutcome {
+    Delivered(BasicResource),
+    Refused(RefusalReason), // The explorer wasn't allowed the cell when it got charged
+    Expired,                // Not fulfilled within the timeout
+    Cancelled,              // The explorer left the planet
+}
+
+impl DeferredOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeferredOutcome::Delivered(_) => "delivered",
+            DeferredOutcome::Refused(_) => "refused",
+            DeferredOutcome::Expired => "expired",
+            DeferredOutcome::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Outcome of a deferred request, sent to the host on behalf of the explorer
+#[derive(Debug)]
+pub struct DeferredDelivery {
+    pub explorer_id: ID,
+    pub resource: BasicResourceType,
+    pub requested_at: Instant,
+    pub outcome: DeferredOutcome,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DeferredRequest {
+    pub(crate) explorer_id: ID,
+    pub(crate) resource: BasicResourceType,
+    pub(crate) requested_at: Instant,
+}
+
+// Requests waiting for the next charged cell, first come first served
+#[derive(Debug, Default)]
+pub(crate) struct Waitlist {
+    config: WaitlistConfig,
+    waiting: VecDeque<DeferredRequest>,
+}
+
+impl Waitlist {
+    pub(crate) fn new(config: WaitlistConfig) -> Self {
+        Self {
+            config,
+            waiting: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.config.delivery.is_some()
+    }
+
+    // Queues a request, returns false if the waitlist is disabled or the explorer has too many waiting
+    pub(crate) fn defer(&mut self, explorer_id: ID, resource: BasicResourceType, now: Instant) -> bool {
+        let waiting = self
+            .waiting
+            .iter()
+            .filter(|request| request.explorer_id == explorer_id)
+            .count();
+        if !self.is_enabled() || waiting >= self.config.max_per_explorer {
+            return false;
+        }
+        self.waiting.push_back(DeferredRequest {
+            explorer_id,
+            resource,
+            requested_at: now,
+        });
+        true
+    }
+
+    pub(crate) fn next(&mut self) -> Option<DeferredRequest> {
+        self.waiting.pop_front()
+    }
+
+    // Puts back a request that couldn't be served yet, keeping its place
+    pub(crate) fn requeue(&mut self, request: DeferredRequest) {
+        self.waiting.push_front(request);
+    }
+
+    pub(crate) fn expire(&mut self, now: Instant) -> Vec<DeferredRequest> {
+        let timeout = self.config.timeout;
+        let (expired, waiting) = std::mem::take(&mut self.waiting)
+            .into_iter()
+            .partition(|request| now.saturating_duration_since(request.requested_at) >= timeout);
+        self.waiting = waiting;
+        expired.into()
+    }
+
+    pub(crate) fn cancel(&mut self, explorer_id: ID) -> Vec<DeferredRequest> {
+        let (cancelled, waiting) = std::mem::take(&mut self.waiting)
+            .into_iter()
+            .partition(|request| request.explorer_id == explorer_id);
+        self.waiting = waiting;
+        cancelled.into()
+    }
+
+    // Sends the outcome to the host, returns false if nobody is listening anymore
+    pub(crate) fn deliver(&self, request: DeferredRequest, outcome: DeferredOutcome) -> bool {
+        let Some(delivery) = &self.config.delivery else {
+            return false;
+        };
+        delivery
+            .send(DeferredDelivery {
+                explorer_id: request.explorer_id,
+                resource: request.resource,
+                requested_at: request.requested_at,
+                outcome,
+            })
+            .is_ok()
+    }
+}